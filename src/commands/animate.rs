@@ -0,0 +1,120 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::animation::NpcAnimation;
+use crate::TRACKER;
+
+fn find_animation(args: &ConsumedArgs<'_>) -> Result<String, CommandError> {
+    SimpleArgConsumer::find_arg(args, "animation")
+        .map(str::to_string)
+        .map_err(|_| CommandError::InvalidConsumption(Some("animation".into())))
+}
+
+pub struct AnimateExecutor;
+
+impl CommandExecutor for AnimateExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let animation: NpcAnimation = find_animation(args)?.parse().map_err(failure)?;
+
+            let npc = resolve_target(sender, args).await?;
+            TRACKER
+                .get()
+                .expect("NPC tracker not initialized")
+                .animate(&npc, animation)
+                .await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Played {animation} on NPC '{}' (ID {})",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Trigger {
+    Click,
+    Approach,
+}
+
+/// Sets or, with `none`, clears the animation an NPC plays by itself.
+pub struct AutoAnimationExecutor(pub Trigger);
+
+impl CommandExecutor for AutoAnimationExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let value = find_animation(args)?;
+            let animation = if value.eq_ignore_ascii_case("none") {
+                None
+            } else {
+                Some(value.parse::<NpcAnimation>().map_err(failure)?)
+            };
+            let distance = match SimpleArgConsumer::find_arg(args, "distance") {
+                Ok(value) => Some(
+                    value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|d| (0.5..=32.0).contains(d))
+                        .ok_or_else(|| {
+                            failure(format!("Invalid distance: {value} (expected 0.5 to 32)"))
+                        })?,
+                ),
+                Err(_) => None,
+            };
+
+            let npc = resolve_target(sender, args).await?;
+            let trigger = match self.0 {
+                Trigger::Click => "clicked",
+                Trigger::Approach => "approached",
+            };
+            let (_, npc) = update_npc(npc.id, |npc| {
+                let animations = &mut npc.animations;
+                match self.0 {
+                    Trigger::Click => animations.on_click = animation,
+                    Trigger::Approach => {
+                        animations.on_approach = animation;
+                        if let Some(distance) = distance {
+                            animations.approach_distance = distance;
+                        }
+                    }
+                }
+                Ok(())
+            })
+            .await?;
+
+            let message = match animation {
+                Some(animation) => format!(
+                    "NPC '{}' (ID {}) now plays {animation} when {trigger}",
+                    npc.display_name, npc.id
+                ),
+                None => format!(
+                    "NPC '{}' (ID {}) no longer animates when {trigger}",
+                    npc.display_name, npc.id
+                ),
+            };
+            sender.send_message(TextComponent::text(message)).await;
+
+            Ok(1)
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+use pumpkin::command::args::{
+    Arg, ArgumentConsumer, ConsumedArgs, FindArg, GetClientSideArgParser,
+};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::tree::RawArgs;
+use pumpkin::command::CommandSender;
+use pumpkin::server::Server;
+use pumpkin_protocol::java::client::play::{
+    ArgumentType, CommandSuggestion, StringProtoArgBehavior, SuggestionProviders,
+};
+use pumpkin_util::text::TextComponent;
+
+use crate::{HOLOGRAMS, REGISTRY};
+
+/// Where a [`NameArgConsumer`] looks up the things it names.
+pub trait NameSource: Send + Sync + 'static {
+    /// Every entry as its ID, name and suggestion tooltip.
+    fn entries() -> impl Future<Output = Vec<(u32, String, String)>> + Send;
+}
+
+/// NPCs, named by key.
+pub struct NpcNames;
+
+impl NameSource for NpcNames {
+    fn entries() -> impl Future<Output = Vec<(u32, String, String)>> + Send {
+        async {
+            let Some(registry) = REGISTRY.get() else {
+                return Vec::new();
+            };
+            registry
+                .all()
+                .await
+                .into_iter()
+                .map(|n| (n.id, n.key, n.display_name))
+                .collect()
+        }
+    }
+}
+
+/// Standalone holograms, named by name and described by their first line.
+pub struct HologramNames;
+
+impl NameSource for HologramNames {
+    fn entries() -> impl Future<Output = Vec<(u32, String, String)>> + Send {
+        async {
+            let Some(registry) = HOLOGRAMS.get() else {
+                return Vec::new();
+            };
+            registry
+                .all()
+                .await
+                .into_iter()
+                .map(|h| {
+                    let first_line = h.lines.first().map(|l| l.text.clone()).unwrap_or_default();
+                    (h.id, h.name, first_line)
+                })
+                .collect()
+        }
+    }
+}
+
+/// A single word naming an entry of `S` by name or numeric ID, tab completed
+/// from `S`.
+pub struct NameArgConsumer<S>(PhantomData<S>);
+
+impl<S> NameArgConsumer<S> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S> Default for NameArgConsumer<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub type NpcArgConsumer = NameArgConsumer<NpcNames>;
+pub type HologramArgConsumer = NameArgConsumer<HologramNames>;
+
+impl<S: NameSource> GetClientSideArgParser for NameArgConsumer<S> {
+    fn get_client_side_parser(&self) -> ArgumentType<'_> {
+        ArgumentType::String(StringProtoArgBehavior::SingleWord)
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<SuggestionProviders> {
+        Some(SuggestionProviders::AskServer)
+    }
+}
+
+impl<S: NameSource> ArgumentConsumer for NameArgConsumer<S> {
+    fn consume<'a, 'b>(
+        &'a self,
+        _sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'b mut RawArgs<'a>,
+    ) -> Pin<Box<dyn Future<Output = Option<Arg<'a>>> + Send + 'a>> {
+        let result = args.pop().map(Arg::Simple);
+        Box::pin(async move { result })
+    }
+
+    fn suggest<'a>(
+        &'a self,
+        _sender: &'a CommandSender,
+        _server: &'a Server,
+        input: &'a str,
+    ) -> Pin<
+        Box<dyn Future<Output = Result<Option<Vec<CommandSuggestion>>, CommandError>> + Send + 'a>,
+    > {
+        Box::pin(async move {
+            let mut entries = S::entries().await;
+            entries.sort_by_key(|(id, _, _)| *id);
+
+            let suggestions = entries
+                .into_iter()
+                .filter(|(id, name, _)| {
+                    name.starts_with(input) || id.to_string().starts_with(input)
+                })
+                .map(|(id, name, tooltip)| {
+                    CommandSuggestion::new(
+                        name,
+                        Some(TextComponent::text(format!("#{id} {tooltip}"))),
+                    )
+                })
+                .collect();
+            Ok(Some(suggestions))
+        })
+    }
+}
+
+impl<'a, S> FindArg<'a> for NameArgConsumer<S> {
+    type Data = &'a str;
+
+    fn find_arg(args: &'a ConsumedArgs, name: &str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Simple(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -5,9 +5,10 @@ use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
 use pumpkin::server::Server;
 use pumpkin_util::text::TextComponent;
 
+use super::failure;
 use crate::npc::skin;
-use crate::npc::NpcLocation;
-use crate::REGISTRY;
+use crate::npc::{player_world, NpcLocation};
+use crate::{REGISTRY, TRACKER};
 
 pub struct CreateExecutor;
 
@@ -15,12 +16,15 @@ impl CommandExecutor for CreateExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a Server,
+        _server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
             let name = SimpleArgConsumer::find_arg(args, "name")
                 .map_err(|_| CommandError::InvalidConsumption(Some("name".into())))?;
+            let key = SimpleArgConsumer::find_arg(args, "key")
+                .ok()
+                .map(str::to_string);
 
             let CommandSender::Player(player) = sender else {
                 sender
@@ -40,6 +44,7 @@ impl CommandExecutor for CreateExecutor {
             let pitch = player.living_entity.entity.pitch.load();
 
             let location = NpcLocation {
+                world: player_world(player),
                 x: pos.x,
                 y: pos.y,
                 z: pos.z,
@@ -48,12 +53,15 @@ impl CommandExecutor for CreateExecutor {
             };
 
             let npc = registry
-                .create(name.to_string(), location, fetched_skin)
-                .await;
+                .create(key, name.to_string(), location, fetched_skin)
+                .await
+                .map_err(failure)?;
 
-            for p in server.get_all_players() {
-                crate::npc::packets::spawn_npc_for_player(&npc, &p).await;
-            }
+            TRACKER
+                .get()
+                .expect("NPC tracker not initialized")
+                .refresh_npc(&npc)
+                .await;
 
             let skin_msg = if has_skin {
                 " with skin"
@@ -62,8 +70,8 @@ impl CommandExecutor for CreateExecutor {
             };
             sender
                 .send_message(TextComponent::text(format!(
-                    "Created NPC '{}' (ID {}){skin_msg}",
-                    npc.name, npc.id
+                    "Created NPC '{}' (key {}, ID {}){skin_msg}",
+                    npc.display_name, npc.key, npc.id
                 )))
                 .await;
 
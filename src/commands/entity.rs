@@ -0,0 +1,188 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::entity::{self, VillagerData};
+use crate::npc::Npc;
+use crate::TRACKER;
+
+fn find_simple<'a>(args: &'a ConsumedArgs<'a>, name: &str) -> Result<&'a str, CommandError> {
+    SimpleArgConsumer::find_arg(args, name)
+        .map_err(|_| CommandError::InvalidConsumption(Some(name.into())))
+}
+
+/// Type and type data changes need a fresh spawn packet, so the NPC is
+/// despawned and spawned again for everyone in range.
+async fn respawn(old: &Npc, new: &Npc) {
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    tracker.forget_npc(old).await;
+    tracker.refresh_npc(new).await;
+}
+
+/// Edits the NPC, e.g. its type data, and respawns it with the changes.
+async fn update_data(
+    npc: &Npc,
+    update: impl FnOnce(&mut Npc) -> Result<(), CommandError>,
+) -> Result<Npc, CommandError> {
+    let (before, after) = update_npc(npc.id, update).await?;
+    respawn(&before, &after).await;
+    Ok(after)
+}
+
+pub struct TypeExecutor;
+
+impl CommandExecutor for TypeExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let entity_type =
+                entity::resolve_entity_type(find_simple(args, "type")?).map_err(failure)?;
+
+            let npc = resolve_target(sender, args).await?;
+            let updated = update_data(&npc, |npc| {
+                npc.entity_type = entity_type;
+                Ok(())
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) is now a {}",
+                    updated.display_name, updated.id, updated.entity_type
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct BabyExecutor;
+
+impl CommandExecutor for BabyExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_target(sender, args).await?;
+            let npc = update_data(&npc, |npc| {
+                if !entity::supports_baby(&npc.entity_type) {
+                    return Err(failure(format!("A {} can't be a baby", npc.entity_type)));
+                }
+                npc.entity_data.baby = !npc.entity_data.baby;
+                Ok(())
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) is {}",
+                    npc.display_name,
+                    npc.id,
+                    if npc.entity_data.baby {
+                        "now a baby"
+                    } else {
+                        "no longer a baby"
+                    }
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct VillagerExecutor;
+
+impl CommandExecutor for VillagerExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let level = match find_simple(args, "level") {
+                Ok(level) => level
+                    .parse::<i32>()
+                    .map_err(|_| failure(format!("Invalid level: {level}")))?,
+                Err(_) => 1,
+            };
+            let villager = VillagerData::new(
+                find_simple(args, "biome")?,
+                find_simple(args, "profession")?,
+                level,
+            )
+            .map_err(failure)?;
+
+            let npc = resolve_target(sender, args).await?;
+            let npc = update_data(&npc, |npc| {
+                if !entity::supports_villager_data(&npc.entity_type) {
+                    return Err(failure(format!(
+                        "A {} has no villager data",
+                        npc.entity_type
+                    )));
+                }
+                npc.entity_data.villager = Some(villager);
+                Ok(())
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Updated the villager data of NPC '{}' (ID {})",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct ColorExecutor;
+
+impl CommandExecutor for ColorExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let color = entity::parse_color(find_simple(args, "color")?).map_err(failure)?;
+
+            let npc = resolve_target(sender, args).await?;
+            let npc = update_data(&npc, |npc| {
+                if !entity::supports_color(&npc.entity_type) {
+                    return Err(failure(format!("A {} has no color", npc.entity_type)));
+                }
+                npc.entity_data.color = Some(color);
+                Ok(())
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Colored NPC '{}' (ID {}) {}",
+                    npc.display_name,
+                    npc.id,
+                    npc.entity_data.color.as_deref().unwrap_or_default()
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::equipment::{EquipmentSlot, NpcItem};
+use crate::npc::packets::send_equipment_for_player;
+use crate::TRACKER;
+
+fn find_slot(args: &ConsumedArgs<'_>) -> Result<EquipmentSlot, CommandError> {
+    SimpleArgConsumer::find_arg(args, "slot")
+        .map_err(|_| CommandError::InvalidConsumption(Some("slot".into())))?
+        .parse()
+        .map_err(failure)
+}
+
+/// Stores the slot's new contents and shows them to everyone viewing the NPC.
+/// Clearing a slot that is already empty fails.
+async fn apply(
+    npc_id: u32,
+    slot: EquipmentSlot,
+    item: Option<NpcItem>,
+) -> Result<(), CommandError> {
+    let (_, npc) = update_npc(npc_id, |npc| {
+        match item {
+            Some(item) => {
+                npc.equipment.insert(slot, item);
+            }
+            None if npc.equipment.remove(&slot).is_none() => {
+                return Err(failure(format!(
+                    "NPC '{}' (ID {}) has nothing in the {slot} slot",
+                    npc.display_name, npc.id
+                )));
+            }
+            None => {}
+        }
+        Ok(())
+    })
+    .await?;
+
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    for p in tracker.viewers(npc.id).await {
+        send_equipment_for_player(&npc, &p, &[slot]).await;
+    }
+    Ok(())
+}
+
+pub struct EquipExecutor;
+
+impl CommandExecutor for EquipExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let slot = find_slot(args)?;
+
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text(
+                        "Only players can equip NPCs, with the item they are holding",
+                    ))
+                    .await;
+                return Ok(0);
+            };
+
+            let npc = resolve_target(sender, args).await?;
+
+            let held = player.inventory().held_item();
+            let stack = held.lock().await.clone();
+            let Some(item) = NpcItem::from_stack(&stack) else {
+                sender
+                    .send_message(TextComponent::text(
+                        "Hold the item to equip in your main hand",
+                    ))
+                    .await;
+                return Ok(0);
+            };
+            let item_id = item.id.clone();
+
+            apply(npc.id, slot, Some(item)).await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Equipped {item_id} in the {slot} slot of NPC '{}' (ID {})",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct UnequipExecutor;
+
+impl CommandExecutor for UnequipExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let slot = find_slot(args)?;
+            let npc = resolve_target(sender, args).await?;
+            apply(npc.id, slot, None).await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Cleared the {slot} slot of NPC '{}' (ID {})",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::color::NamedColor;
+use crate::npc::packets::{update_state_for_player, update_team_for_player};
+use crate::TRACKER;
+
+pub struct GlowToggleExecutor;
+
+impl CommandExecutor for GlowToggleExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_target(sender, args).await?;
+            let (_, npc) = update_npc(npc.id, |npc| {
+                npc.glowing = !npc.glowing;
+                Ok(())
+            })
+            .await?;
+
+            let tracker = TRACKER.get().expect("NPC tracker not initialized");
+            for p in tracker.viewers(npc.id).await {
+                update_state_for_player(&npc, &p).await;
+            }
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Glowing {} for NPC '{}' (ID {})",
+                    if npc.glowing { "enabled" } else { "disabled" },
+                    npc.display_name,
+                    npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct GlowColorExecutor;
+
+impl CommandExecutor for GlowColorExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let value = SimpleArgConsumer::find_arg(args, "color")
+                .map_err(|_| CommandError::InvalidConsumption(Some("color".into())))?;
+            let color = if value.eq_ignore_ascii_case("reset") {
+                None
+            } else {
+                Some(value.parse::<NamedColor>().map_err(failure)?)
+            };
+
+            let npc = resolve_target(sender, args).await?;
+            let (_, npc) = update_npc(npc.id, |npc| {
+                npc.glow_color = color;
+                Ok(())
+            })
+            .await?;
+
+            let tracker = TRACKER.get().expect("NPC tracker not initialized");
+            for p in tracker.viewers(npc.id).await {
+                update_team_for_player(&npc, &p).await;
+            }
+
+            let color = color.map_or("white".to_string(), |c| c.to_string());
+            let hint = if npc.glowing {
+                ""
+            } else {
+                " (glowing is off, use /npc glow to turn it on)"
+            };
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) now glows {color}{hint}",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
@@ -0,0 +1,406 @@
+use pumpkin::command::args::message::MsgArgConsumer;
+use pumpkin::command::args::position_3d::Position3DArgumentConsumer;
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::args::HologramArgConsumer;
+use super::failure;
+use super::hologram::{line_list, LineEdit, StyleOption};
+use crate::holo::Hologram;
+use crate::npc::hologram::check_line;
+use crate::npc::{player_world, HologramLine, NpcLocation};
+use crate::{HOLOGRAMS, TRACKER};
+
+async fn resolve_hologram(args: &ConsumedArgs<'_>) -> Result<Hologram, CommandError> {
+    let query = HologramArgConsumer::find_arg(args, "hologram")?;
+    HOLOGRAMS
+        .get()
+        .expect("Hologram registry not initialized")
+        .find(query)
+        .await
+        .ok_or_else(|| failure(format!("No hologram found with name or ID '{query}'")))
+}
+
+/// Edits the hologram through `HologramRegistry::update`. Returns it before
+/// and after the edit.
+async fn update_hologram(
+    id: u32,
+    edit: impl FnOnce(&mut Hologram) -> Result<(), CommandError>,
+) -> Result<(Hologram, Hologram), CommandError> {
+    HOLOGRAMS
+        .get()
+        .expect("Hologram registry not initialized")
+        .update(id, edit)
+        .await
+        .ok_or_else(|| failure("Hologram not found"))?
+}
+
+/// Swaps `before` for `after` on every client, spawning it only for players
+/// in range of its new state.
+async fn replace(before: &Hologram, after: &Hologram) {
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    tracker.forget_hologram(before).await;
+    tracker.refresh_hologram(after).await;
+}
+
+pub struct HoloCreateExecutor;
+
+impl CommandExecutor for HoloCreateExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text("Only players can use this command"))
+                    .await;
+                return Ok(0);
+            };
+
+            let name = SimpleArgConsumer::find_arg(args, "name")
+                .map_err(|_| CommandError::InvalidConsumption(Some("name".into())))?
+                .to_string();
+            let lines: Vec<String> = MsgArgConsumer::find_arg(args, "text")
+                .ok()
+                .into_iter()
+                .collect();
+            for line in &lines {
+                check_line(line).map_err(failure)?;
+            }
+
+            let pos = player.living_entity.entity.pos.load();
+            let location = NpcLocation {
+                world: player_world(player),
+                x: pos.x,
+                y: pos.y,
+                z: pos.z,
+                yaw: player.living_entity.entity.yaw.load(),
+                pitch: 0.0,
+            };
+
+            let registry = HOLOGRAMS.get().expect("Hologram registry not initialized");
+            let hologram = registry
+                .create(name, location, lines)
+                .await
+                .map_err(failure)?;
+            TRACKER
+                .get()
+                .expect("NPC tracker not initialized")
+                .refresh_hologram(&hologram)
+                .await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Created hologram '{}' (ID {}) at your position",
+                    hologram.name, hologram.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct HoloRemoveExecutor;
+
+impl CommandExecutor for HoloRemoveExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let hologram = resolve_hologram(args).await?;
+            let registry = HOLOGRAMS.get().expect("Hologram registry not initialized");
+            let hologram = registry
+                .remove(hologram.id)
+                .await
+                .ok_or_else(|| failure("Hologram not found"))?;
+            TRACKER
+                .get()
+                .expect("NPC tracker not initialized")
+                .forget_hologram(&hologram)
+                .await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Removed hologram '{}' (ID {})",
+                    hologram.name, hologram.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct HoloMoveHereExecutor;
+
+impl CommandExecutor for HoloMoveHereExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text("Only players can use this command"))
+                    .await;
+                return Ok(0);
+            };
+
+            let hologram = resolve_hologram(args).await?;
+            let pos = player.living_entity.entity.pos.load();
+            let (before, after) = update_hologram(hologram.id, |hologram| {
+                let location = &mut hologram.location;
+                location.world = player_world(player);
+                location.x = pos.x;
+                location.y = pos.y;
+                location.z = pos.z;
+                Ok(())
+            })
+            .await?;
+            replace(&before, &after).await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Moved hologram '{}' (ID {}) to your position",
+                    after.name, after.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct HoloMoveToExecutor;
+
+impl CommandExecutor for HoloMoveToExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let pos = Position3DArgumentConsumer::find_arg(args, "position")?;
+
+            let hologram = resolve_hologram(args).await?;
+            let (before, after) = update_hologram(hologram.id, |hologram| {
+                let location = &mut hologram.location;
+                location.x = pos.x;
+                location.y = pos.y;
+                location.z = pos.z;
+                Ok(())
+            })
+            .await?;
+            replace(&before, &after).await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Moved hologram '{}' (ID {}) to ({:.1}, {:.1}, {:.1})",
+                    after.name, after.id, after.location.x, after.location.y, after.location.z
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct HoloListExecutor;
+
+impl CommandExecutor for HoloListExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let registry = HOLOGRAMS.get().expect("Hologram registry not initialized");
+            let mut holograms = registry.all().await;
+            holograms.sort_by_key(|h| h.id);
+
+            if holograms.is_empty() {
+                sender
+                    .send_message(TextComponent::text("No holograms exist"))
+                    .await;
+                return Ok(0);
+            }
+
+            let mut msg = format!("Holograms ({}):\n", holograms.len());
+            for hologram in &holograms {
+                msg.push_str(&format!(
+                    "  #{} {} ({} line(s)) at ({:.1}, {:.1}, {:.1}) in {}\n",
+                    hologram.id,
+                    hologram.name,
+                    hologram.lines.len(),
+                    hologram.location.x,
+                    hologram.location.y,
+                    hologram.location.z,
+                    hologram.location.world
+                ));
+            }
+
+            sender.send_message(TextComponent::text(msg)).await;
+
+            Ok(holograms.len() as i32)
+        })
+    }
+}
+
+pub struct HoloLinesExecutor;
+
+impl CommandExecutor for HoloLinesExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let hologram = resolve_hologram(args).await?;
+            if hologram.lines.is_empty() {
+                sender
+                    .send_message(TextComponent::text(format!(
+                        "Hologram '{}' (ID {}) has no lines",
+                        hologram.name, hologram.id
+                    )))
+                    .await;
+                return Ok(0);
+            }
+
+            let header = format!(
+                "Lines of hologram '{}' (ID {}):",
+                hologram.name, hologram.id
+            );
+            let command = format!("/holo lines {}", hologram.name);
+            sender
+                .send_message(line_list(header, &command, &hologram.lines))
+                .await;
+
+            Ok(hologram.lines.len() as i32)
+        })
+    }
+}
+
+pub struct HoloEditExecutor(pub LineEdit);
+
+impl CommandExecutor for HoloEditExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let hologram = resolve_hologram(args).await?;
+            let mut action = String::new();
+            // Every line gets a new entity ID, so the old lines are despawned
+            // as a whole
+            let (before, after) = update_hologram(hologram.id, |hologram| {
+                let mut lines: Vec<String> =
+                    hologram.lines.iter().map(|l| l.text.clone()).collect();
+                action = self.0.apply(&mut lines, args)?;
+                hologram.lines = lines.into_iter().map(HologramLine::new).collect();
+                Ok(())
+            })
+            .await?;
+            replace(&before, &after).await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "{action} of hologram '{}' (ID {})",
+                    after.name, after.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct HoloStyleExecutor(pub StyleOption);
+
+impl CommandExecutor for HoloStyleExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let hologram = resolve_hologram(args).await?;
+            let (before, after) = update_hologram(hologram.id, |hologram| {
+                self.0.apply(&mut hologram.style, args)
+            })
+            .await?;
+            replace(&before, &after).await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Updated the {} of hologram '{}' (ID {})",
+                    self.0.label(),
+                    after.name,
+                    after.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct HoloServerExecutor;
+
+impl CommandExecutor for HoloServerExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let hologram = resolve_hologram(args).await?;
+            let server = SimpleArgConsumer::find_arg(args, "server").ok();
+            if let Some(server) = server.filter(|s| !crate::status::has_server(s)) {
+                return Err(failure(format!(
+                    "Unknown server '{server}'. Use /npc server add <name> <address> first"
+                )));
+            }
+
+            let (before, after) = update_hologram(hologram.id, |hologram| {
+                hologram.server = server.map(str::to_string);
+                Ok(())
+            })
+            .await?;
+            replace(&before, &after).await;
+
+            let message = match &after.server {
+                Some(server) => format!(
+                    "Hologram '{}' (ID {}) now shows the status of server '{server}'",
+                    after.name, after.id
+                ),
+                None => format!(
+                    "Hologram '{}' (ID {}) no longer shows a server status",
+                    after.name, after.id
+                ),
+            };
+            sender.send_message(TextComponent::text(message)).await;
+
+            Ok(1)
+        })
+    }
+}
@@ -1,11 +1,45 @@
 use pumpkin::command::args::message::MsgArgConsumer;
+use pumpkin::command::args::simple::SimpleArgConsumer;
 use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
 use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
 use pumpkin::server::Server;
+use pumpkin_util::text::click::ClickEvent;
+use pumpkin_util::text::hover::HoverEvent;
 use pumpkin_util::text::TextComponent;
 
-use super::find_npc_in_crosshair;
-use crate::REGISTRY;
+use super::{failure, resolve_target, update_npc};
+use crate::npc::hologram::{
+    check_line, despawn_holograms_for_player, spawn_holograms_for_player, Background, HologramStyle,
+};
+use crate::npc::{HologramLine, Npc};
+use crate::TRACKER;
+
+/// Replaces the hologram stack of `before` with the one of `after` for
+/// everyone viewing the NPC.
+async fn respawn(before: &Npc, after: &Npc) {
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    for p in tracker.viewers(after.id).await {
+        despawn_holograms_for_player(before, &p).await;
+        spawn_holograms_for_player(after, &p).await;
+    }
+}
+
+/// Zero-based index of the 1-based line number in argument `name`, which can
+/// be at most `max`.
+fn line_index(args: &ConsumedArgs<'_>, name: &str, max: usize) -> Result<usize, CommandError> {
+    let value = SimpleArgConsumer::find_arg(args, name)
+        .map_err(|_| CommandError::InvalidConsumption(Some(name.into())))?;
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|line| (1..=max).contains(line))
+        .map(|line| line - 1)
+        .ok_or_else(|| match max {
+            0 => failure("There are no hologram lines"),
+            _ => failure(format!("Invalid line: {value} (expected 1 to {max})")),
+        })
+}
 
 pub struct HologramAddExecutor;
 
@@ -13,7 +47,7 @@ impl CommandExecutor for HologramAddExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a Server,
+        _server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
@@ -24,57 +58,245 @@ impl CommandExecutor for HologramAddExecutor {
                     ))
                 })?
                 .to_string();
+            check_line(&text).map_err(failure)?;
 
-            let CommandSender::Player(player) = sender else {
-                sender
-                    .send_message(TextComponent::text("Only players can use this command"))
-                    .await;
-                return Ok(0);
-            };
+            let npc = resolve_target(sender, args).await?;
+            let (npc_before, npc_after) = update_npc(npc.id, |npc| {
+                npc.holograms.push(HologramLine::new(text.clone()));
+                Ok(())
+            })
+            .await?;
+            respawn(&npc_before, &npc_after).await;
 
-            let registry = REGISTRY.get().expect("NPC registry not initialized");
-            let npcs = registry.all().await;
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Added hologram '{}' to NPC '{}' (ID {})",
+                    text, npc_after.display_name, npc_after.id
+                )))
+                .await;
 
-            if npcs.is_empty() {
-                sender
-                    .send_message(TextComponent::text("No NPCs exist"))
-                    .await;
-                return Ok(0);
-            }
+            Ok(1)
+        })
+    }
+}
 
-            let Some(npc_id) = find_npc_in_crosshair(player, &npcs) else {
-                sender
-                    .send_message(TextComponent::text("No NPC found in crosshair"))
-                    .await;
-                return Ok(0);
-            };
+#[derive(Clone, Copy)]
+pub enum StyleOption {
+    Background,
+    Billboard,
+    Alignment,
+    LineWidth,
+    Shadow,
+    Scale,
+    HeightOffset,
+    LineSpacing,
+}
 
-            let Some(npc_before) = registry.get(npc_id).await else {
-                sender
-                    .send_message(TextComponent::text("NPC not found"))
-                    .await;
-                return Ok(0);
-            };
+impl StyleOption {
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            StyleOption::Background => "background",
+            StyleOption::Billboard => "billboard",
+            StyleOption::Alignment => "alignment",
+            StyleOption::LineWidth => "line width",
+            StyleOption::Shadow => "text shadow",
+            StyleOption::Scale => "scale",
+            StyleOption::HeightOffset => "height offset",
+            StyleOption::LineSpacing => "line spacing",
+        }
+    }
 
-            for p in server.get_all_players() {
-                crate::npc::packets::despawn_holograms_for_player(&npc_before, &p).await;
+    /// Applies the command's `value` argument. A missing background resets it
+    /// to the client default; shadow toggles when no value is given.
+    pub(super) fn apply(
+        self,
+        style: &mut HologramStyle,
+        args: &ConsumedArgs<'_>,
+    ) -> Result<(), CommandError> {
+        let value = SimpleArgConsumer::find_arg(args, "value").ok();
+        let required =
+            || value.ok_or_else(|| CommandError::InvalidConsumption(Some("value".into())));
+        match self {
+            StyleOption::Background => {
+                style.background = match value {
+                    Some(color) => {
+                        let opacity = match SimpleArgConsumer::find_arg(args, "opacity") {
+                            Ok(opacity) => opacity
+                                .parse()
+                                .map_err(|_| failure(format!("Invalid opacity: {opacity}")))?,
+                            Err(_) => 100,
+                        };
+                        Some(Background::parse(color, opacity).map_err(failure)?)
+                    }
+                    None => None,
+                };
+            }
+            StyleOption::Billboard => style.billboard = required()?.parse().map_err(failure)?,
+            StyleOption::Alignment => style.alignment = required()?.parse().map_err(failure)?,
+            StyleOption::LineWidth => {
+                let value = required()?;
+                style.line_width = value
+                    .parse()
+                    .ok()
+                    .filter(|w| *w > 0)
+                    .ok_or_else(|| failure(format!("Invalid line width: {value}")))?;
             }
+            StyleOption::Shadow => {
+                style.shadow = match value {
+                    Some(value) => value.parse().map_err(|_| {
+                        failure(format!("Invalid value: {value} (expected true or false)"))
+                    })?,
+                    None => !style.shadow,
+                };
+            }
+            StyleOption::Scale => {
+                let value = required()?;
+                style.scale = value
+                    .parse()
+                    .ok()
+                    .filter(|s: &f32| s.is_finite() && *s > 0.0)
+                    .ok_or_else(|| failure(format!("Invalid scale: {value}")))?;
+            }
+            StyleOption::HeightOffset => {
+                let value = required()?;
+                style.height_offset = value
+                    .parse()
+                    .ok()
+                    .filter(|o: &f64| o.is_finite() && o.abs() <= 16.0)
+                    .ok_or_else(|| failure(format!("Invalid height offset: {value}")))?;
+            }
+            StyleOption::LineSpacing => {
+                let value = required()?;
+                style.line_spacing = value
+                    .parse()
+                    .ok()
+                    .filter(|s: &f64| s.is_finite() && *s > 0.0 && *s <= 4.0)
+                    .ok_or_else(|| failure(format!("Invalid line spacing: {value}")))?;
+            }
+        }
+        Ok(())
+    }
+}
 
-            let Some(npc_after) = registry.add_hologram(npc_id, text.clone()).await else {
-                sender
-                    .send_message(TextComponent::text("NPC not found"))
-                    .await;
-                return Ok(0);
-            };
+pub struct HologramStyleExecutor(pub StyleOption);
+
+impl CommandExecutor for HologramStyleExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_target(sender, args).await?;
+            let (npc_before, npc_after) =
+                update_npc(npc.id, |npc| self.0.apply(&mut npc.hologram_style, args)).await?;
+            respawn(&npc_before, &npc_after).await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Updated the hologram {} of NPC '{}' (ID {})",
+                    self.0.label(),
+                    npc_after.display_name,
+                    npc_after.id
+                )))
+                .await;
 
-            for p in server.get_all_players() {
-                crate::npc::packets::spawn_holograms_for_player(&npc_after, &p).await;
+            Ok(1)
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum LineEdit {
+    Add,
+    Set,
+    Remove,
+    Insert,
+    Move,
+    Clear,
+}
+
+impl LineEdit {
+    /// Edits `lines` as the command's arguments ask and describes the change.
+    pub(super) fn apply(
+        self,
+        lines: &mut Vec<String>,
+        args: &ConsumedArgs<'_>,
+    ) -> Result<String, CommandError> {
+        let len = lines.len();
+        let text = || {
+            let text = MsgArgConsumer::find_arg(args, "text")
+                .map_err(|_| CommandError::InvalidConsumption(Some("text".into())))?;
+            check_line(&text).map_err(failure)?;
+            Ok::<_, CommandError>(text)
+        };
+
+        Ok(match self {
+            LineEdit::Add => {
+                lines.push(text()?);
+                format!("Added line {}", len + 1)
+            }
+            LineEdit::Set => {
+                let line = line_index(args, "line", len)?;
+                lines[line] = text()?;
+                format!("Set line {}", line + 1)
             }
+            LineEdit::Remove => {
+                let line = line_index(args, "line", len)?;
+                lines.remove(line);
+                format!("Removed line {}", line + 1)
+            }
+            LineEdit::Insert => {
+                let line = line_index(args, "line", len + 1)?;
+                lines.insert(line, text()?);
+                format!("Inserted line {}", line + 1)
+            }
+            LineEdit::Move => {
+                let from = line_index(args, "from", len)?;
+                let to = line_index(args, "to", len)?;
+                let text = lines.remove(from);
+                lines.insert(to, text);
+                format!("Moved line {} to line {}", from + 1, to + 1)
+            }
+            LineEdit::Clear => {
+                if lines.is_empty() {
+                    return Err(failure("There are no hologram lines"));
+                }
+                lines.clear();
+                format!("Removed {len} hologram line(s)")
+            }
+        })
+    }
+}
+
+pub struct HologramEditExecutor(pub LineEdit);
+
+impl CommandExecutor for HologramEditExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_target(sender, args).await?;
+            let mut action = String::new();
+            // Every line gets a new entity ID, so the old stack is despawned
+            // as a whole
+            let (npc_before, npc_after) = update_npc(npc.id, |npc| {
+                let mut lines: Vec<String> = npc.holograms.iter().map(|l| l.text.clone()).collect();
+                action = self.0.apply(&mut lines, args)?;
+                npc.holograms = lines.into_iter().map(HologramLine::new).collect();
+                Ok(())
+            })
+            .await?;
+            respawn(&npc_before, &npc_after).await;
 
             sender
                 .send_message(TextComponent::text(format!(
-                    "Added hologram '{}' to NPC '{}' (ID {})",
-                    text, npc_after.name, npc_after.id
+                    "{action} of NPC '{}' (ID {})",
+                    npc_after.display_name, npc_after.id
                 )))
                 .await;
 
@@ -82,3 +304,58 @@ impl CommandExecutor for HologramAddExecutor {
         })
     }
 }
+
+/// `header` followed by the lines. Clicking a line puts `{command} set` with
+/// its current text in the chat box, ready to edit.
+pub(super) fn line_list(header: String, command: &str, lines: &[HologramLine]) -> TextComponent {
+    let mut msg = TextComponent::text(header);
+    for (i, line) in lines.iter().enumerate() {
+        let edit = format!("{command} set {} {}", i + 1, line.text);
+        let hover = TextComponent::text(format!("Click to edit line {}", i + 1));
+        msg = msg.add_child(
+            TextComponent::text(format!("\n  {}. {}", i + 1, line.text))
+                .click_event(ClickEvent::SuggestCommand {
+                    command: edit.into(),
+                })
+                .hover_event(HoverEvent::show_text(hover)),
+        );
+    }
+    msg
+}
+
+pub struct HologramListExecutor;
+
+impl CommandExecutor for HologramListExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_target(sender, args).await?;
+            if npc.holograms.is_empty() {
+                sender
+                    .send_message(TextComponent::text(format!(
+                        "NPC '{}' (ID {}) has no hologram lines",
+                        npc.display_name, npc.id
+                    )))
+                    .await;
+                return Ok(0);
+            }
+
+            let header = format!(
+                "Hologram lines of NPC '{}' (ID {}):",
+                npc.display_name, npc.id
+            );
+            let msg = line_list(
+                header,
+                &format!("/npc hologram {}", npc.key),
+                &npc.holograms,
+            );
+            sender.send_message(msg).await;
+
+            Ok(npc.holograms.len() as i32)
+        })
+    }
+}
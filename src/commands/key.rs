@@ -0,0 +1,40 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target};
+use crate::REGISTRY;
+
+pub struct KeyExecutor;
+
+impl CommandExecutor for KeyExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let key = SimpleArgConsumer::find_arg(args, "key")
+                .map_err(|_| CommandError::InvalidConsumption(Some("key".into())))?
+                .to_string();
+
+            let target = resolve_target(sender, args).await?;
+            let registry = REGISTRY.get().expect("NPC registry not initialized");
+            let old_key = target.key;
+            let npc = registry.set_key(target.id, key).await.map_err(failure)?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Changed key of NPC '{}' (ID {}) from {old_key} to {}",
+                    npc.display_name, npc.id, npc.key
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
@@ -0,0 +1,135 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::layers::MainHand;
+use crate::npc::packets::update_skin_options_for_player;
+use crate::npc::Npc;
+use crate::TRACKER;
+
+/// Like `resolve_target`, but only accepts player NPCs, the only ones with a skin.
+async fn resolve_player_npc(
+    sender: &CommandSender,
+    args: &ConsumedArgs<'_>,
+) -> Result<Npc, CommandError> {
+    let npc = resolve_target(sender, args).await?;
+    if !npc.is_player() {
+        return Err(failure(format!(
+            "NPC '{}' (ID {}) is a {} and has no skin layers",
+            npc.display_name, npc.id, npc.entity_type
+        )));
+    }
+    Ok(npc)
+}
+
+async fn refresh_viewers(npc: &Npc) {
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    for p in tracker.viewers(npc.id).await {
+        update_skin_options_for_player(npc, &p).await;
+    }
+}
+
+pub struct LayersExecutor;
+
+impl CommandExecutor for LayersExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_player_npc(sender, args).await?;
+
+            let shown = npc.skin_layers.shown();
+            let shown = if shown.is_empty() {
+                "none".to_string()
+            } else {
+                shown.join(", ")
+            };
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) shows layers: {shown}; main hand: {}",
+                    npc.display_name, npc.id, npc.main_hand
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct LayerToggleExecutor;
+
+impl CommandExecutor for LayerToggleExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let layer = SimpleArgConsumer::find_arg(args, "layer")
+                .map_err(|_| CommandError::InvalidConsumption(Some("layer".into())))?;
+
+            let npc = resolve_player_npc(sender, args).await?;
+            let mut on = false;
+            let (_, npc) = update_npc(npc.id, |npc| {
+                on = npc.skin_layers.toggle(layer).map_err(failure)?;
+                Ok(())
+            })
+            .await?;
+            refresh_viewers(&npc).await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "{} the {layer} layer of NPC '{}' (ID {})",
+                    if on { "Showing" } else { "Hiding" },
+                    npc.display_name,
+                    npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct MainHandExecutor;
+
+impl CommandExecutor for MainHandExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let hand: MainHand = SimpleArgConsumer::find_arg(args, "hand")
+                .map_err(|_| CommandError::InvalidConsumption(Some("hand".into())))?
+                .parse()
+                .map_err(failure)?;
+
+            let npc = resolve_player_npc(sender, args).await?;
+            let (_, npc) = update_npc(npc.id, |npc| {
+                npc.main_hand = hand;
+                Ok(())
+            })
+            .await?;
+            refresh_viewers(&npc).await;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) is now {hand}-handed",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
@@ -16,7 +16,8 @@ impl CommandExecutor for ListExecutor {
     ) -> CommandResult<'a> {
         Box::pin(async move {
             let registry = REGISTRY.get().expect("NPC registry not initialized");
-            let npcs = registry.all().await;
+            let mut npcs = registry.all().await;
+            npcs.sort_by_key(|n| n.id);
 
             if npcs.is_empty() {
                 sender
@@ -28,8 +29,14 @@ impl CommandExecutor for ListExecutor {
             let mut msg = format!("NPCs ({}):\n", npcs.len());
             for npc in &npcs {
                 msg.push_str(&format!(
-                    "  #{} '{}' at ({:.1}, {:.1}, {:.1})\n",
-                    npc.id, npc.name, npc.location.x, npc.location.y, npc.location.z
+                    "  #{} {} '{}' at ({:.1}, {:.1}, {:.1}) in {}\n",
+                    npc.id,
+                    npc.key,
+                    npc.display_name,
+                    npc.location.x,
+                    npc.location.y,
+                    npc.location.z,
+                    npc.location.world
                 ));
             }
 
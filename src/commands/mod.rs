@@ -1,19 +1,102 @@
+pub mod animate;
+pub mod args;
 pub mod create;
+pub mod entity;
+pub mod equip;
+pub mod glow;
+pub mod holo;
 pub mod hologram;
+pub mod key;
+pub mod layers;
 pub mod list;
 pub mod looknear;
+pub mod nametag;
+pub mod pose;
+pub mod position;
+pub mod reload;
 pub mod remove;
+pub mod rename;
+pub mod scale;
+pub mod select;
 pub mod server;
+pub mod storage;
+pub mod tab;
 
 use pumpkin::command::args::message::MsgArgConsumer;
+use pumpkin::command::args::position_3d::Position3DArgumentConsumer;
 use pumpkin::command::args::simple::SimpleArgConsumer;
-use pumpkin::command::tree::builder::{argument, literal};
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::tree::builder::{argument, literal, NonLeafNodeBuilder};
 use pumpkin::command::tree::CommandTree;
+use pumpkin::command::{CommandExecutor, CommandSender};
 use pumpkin::entity::player::Player;
+use pumpkin::net::ClientPlatform;
+use pumpkin_util::text::TextComponent;
 
-use crate::npc::Npc;
+use crate::npc::{player_world, Npc};
+use crate::REGISTRY;
+use args::{HologramArgConsumer, NpcArgConsumer};
+use hologram::{LineEdit, StyleOption};
+
+pub(crate) fn failure(message: impl Into<String>) -> CommandError {
+    CommandError::CommandFailed(TextComponent::text(message.into()))
+}
+
+/// Edits the NPC through `NpcRegistry::update`, so concurrent commands on
+/// the same NPC can't undo each other. Returns it before and after the edit.
+pub(crate) async fn update_npc(
+    id: u32,
+    edit: impl FnOnce(&mut Npc) -> Result<(), CommandError>,
+) -> Result<(Npc, Npc), CommandError> {
+    REGISTRY
+        .get()
+        .expect("NPC registry not initialized")
+        .update(id, edit)
+        .await
+        .ok_or_else(|| failure("NPC not found"))?
+}
+
+/// The NPC a command applies to: the one named by the `npc` argument if
+/// present, otherwise the one in the sender's crosshair, otherwise the
+/// sender's selected NPC.
+pub async fn resolve_target(
+    sender: &CommandSender,
+    args: &ConsumedArgs<'_>,
+) -> Result<Npc, CommandError> {
+    let registry = REGISTRY.get().expect("NPC registry not initialized");
+
+    if let Ok(query) = NpcArgConsumer::find_arg(args, "npc") {
+        return registry
+            .find(query)
+            .await
+            .ok_or_else(|| failure(format!("No NPC found with key or ID '{query}'")));
+    }
+
+    let CommandSender::Player(player) = sender else {
+        return Err(failure("Specify an NPC by key or ID"));
+    };
+
+    let npcs = registry.all().await;
+    if npcs.is_empty() {
+        return Err(failure("No NPCs exist"));
+    }
+    let npc_id = match find_npc_in_crosshair(player, &npcs) {
+        Some(id) => id,
+        None => registry
+            .get_selected(&player.gameprofile.id)
+            .await
+            .ok_or_else(|| failure("No NPC found in crosshair and no NPC selected"))?,
+    };
+    npcs.into_iter()
+        .find(|n| n.id == npc_id)
+        .ok_or_else(|| failure("NPC not found"))
+}
 
 pub fn find_npc_in_crosshair(player: &Player, npcs: &[Npc]) -> Option<u32> {
+    let ClientPlatform::Java(java) = &player.client else {
+        return None;
+    };
     let pos = player.living_entity.entity.pos.load();
     let yaw = player.living_entity.entity.yaw.load();
     let pitch = player.living_entity.entity.pitch.load();
@@ -25,12 +108,13 @@ pub fn find_npc_in_crosshair(player: &Player, npcs: &[Npc]) -> Option<u32> {
     let look_z = yaw_rad.cos() * pitch_rad.cos();
 
     let eye_y = pos.y + 1.62;
+    let world = player_world(player);
 
     let mut best: Option<(u32, f64)> = None;
 
-    for npc in npcs {
+    for npc in npcs.iter().filter(|n| n.location.world == world) {
         let dx = npc.location.x - pos.x;
-        let dy = (npc.location.y + 0.9) - eye_y;
+        let dy = (npc.location.y + npc.model_height(java) / 2.0) - eye_y;
         let dz = npc.location.z - pos.z;
         let dist = (dx * dx + dy * dy + dz * dz).sqrt();
 
@@ -43,7 +127,9 @@ pub fn find_npc_in_crosshair(player: &Player, npcs: &[Npc]) -> Option<u32> {
         let nz = dz / dist;
         let dot = look_x * nx + look_y * ny + look_z * nz;
 
-        if dot > 0.9 && best.is_none_or(|(_, d)| dot > d) {
+        // A cone of about 26 degrees at normal size, wider for bigger NPCs
+        let threshold = (0.484 * npc.scale_seen_by(java)).atan().cos();
+        if dot > threshold && best.is_none_or(|(_, d)| dot > d) {
             best = Some((npc.id, dot));
         }
     }
@@ -51,18 +137,262 @@ pub fn find_npc_in_crosshair(player: &Player, npcs: &[Npc]) -> Option<u32> {
     best.map(|(id, _)| id)
 }
 
+/// Adds the same subcommands under `node` itself (targeting the crosshair)
+/// and under an `<npc>` argument that names the NPC explicitly.
+fn with_target(
+    node: NonLeafNodeBuilder,
+    subcommands: fn(NonLeafNodeBuilder) -> NonLeafNodeBuilder,
+) -> NonLeafNodeBuilder {
+    subcommands(node).then(subcommands(argument("npc", NpcArgConsumer::new())))
+}
+
+fn move_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    node.then(literal("here").execute(position::MoveHereExecutor))
+        .then(argument("position", Position3DArgumentConsumer).execute(position::MoveToExecutor))
+}
+
+fn rotate_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    node.then(
+        argument("yaw", SimpleArgConsumer)
+            .then(argument("pitch", SimpleArgConsumer).execute(position::RotateExecutor)),
+    )
+}
+
+fn layers_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    node.execute(layers::LayersExecutor)
+        .then(
+            literal("toggle")
+                .then(argument("layer", SimpleArgConsumer).execute(layers::LayerToggleExecutor)),
+        )
+        .then(
+            literal("mainhand")
+                .then(argument("hand", SimpleArgConsumer).execute(layers::MainHandExecutor)),
+        )
+}
+
+fn glow_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    node.execute(glow::GlowToggleExecutor).then(
+        literal("color")
+            .then(argument("color", SimpleArgConsumer).execute(glow::GlowColorExecutor)),
+    )
+}
+
+fn nametag_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    use nametag::{NametagPart, NametagTextExecutor};
+
+    node.execute(nametag::NametagToggleExecutor)
+        .then(
+            literal("prefix")
+                .execute(NametagTextExecutor(NametagPart::Prefix))
+                .then(
+                    argument("text", MsgArgConsumer)
+                        .execute(NametagTextExecutor(NametagPart::Prefix)),
+                ),
+        )
+        .then(
+            literal("suffix")
+                .execute(NametagTextExecutor(NametagPart::Suffix))
+                .then(
+                    argument("text", MsgArgConsumer)
+                        .execute(NametagTextExecutor(NametagPart::Suffix)),
+                ),
+        )
+}
+
+fn tab_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    use tab::{TabOption, TabSetExecutor};
+
+    node.execute(tab::TabToggleExecutor)
+        .then(
+            literal("name")
+                .execute(TabSetExecutor(TabOption::Name))
+                .then(argument("value", MsgArgConsumer).execute(TabSetExecutor(TabOption::Name))),
+        )
+        .then(
+            literal("latency").then(
+                argument("value", SimpleArgConsumer).execute(TabSetExecutor(TabOption::Latency)),
+            ),
+        )
+        .then(literal("gamemode").then(
+            argument("value", SimpleArgConsumer).execute(TabSetExecutor(TabOption::GameMode)),
+        ))
+        .then(
+            literal("order").then(
+                argument("value", SimpleArgConsumer).execute(TabSetExecutor(TabOption::Order)),
+            ),
+        )
+        .then(
+            literal("group")
+                .execute(TabSetExecutor(TabOption::Group))
+                .then(
+                    argument("value", SimpleArgConsumer).execute(TabSetExecutor(TabOption::Group)),
+                ),
+        )
+}
+
+fn animate_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    use animate::{AutoAnimationExecutor, Trigger};
+
+    node.then(literal("onclick").then(
+        argument("animation", SimpleArgConsumer).execute(AutoAnimationExecutor(Trigger::Click)),
+    ))
+    .then(
+        literal("onapproach").then(
+            argument("animation", SimpleArgConsumer)
+                .execute(AutoAnimationExecutor(Trigger::Approach))
+                .then(
+                    argument("distance", SimpleArgConsumer)
+                        .execute(AutoAnimationExecutor(Trigger::Approach)),
+                ),
+        ),
+    )
+    .then(argument("animation", SimpleArgConsumer).execute(animate::AnimateExecutor))
+}
+
+fn villager_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    node.then(
+        argument("biome", SimpleArgConsumer).then(
+            argument("profession", SimpleArgConsumer)
+                .execute(entity::VillagerExecutor)
+                .then(argument("level", SimpleArgConsumer).execute(entity::VillagerExecutor)),
+        ),
+    )
+}
+
+/// Line editing shared by NPC and standalone holograms.
+fn hologram_line_subcommands<E: CommandExecutor + 'static>(
+    node: NonLeafNodeBuilder,
+    executor: fn(LineEdit) -> E,
+) -> NonLeafNodeBuilder {
+    let line = |name| argument(name, SimpleArgConsumer);
+    let text = |edit| argument("text", MsgArgConsumer).execute(executor(edit));
+    node.then(literal("set").then(line("line").then(text(LineEdit::Set))))
+        .then(literal("insert").then(line("line").then(text(LineEdit::Insert))))
+        .then(literal("remove").then(line("line").execute(executor(LineEdit::Remove))))
+        .then(literal("move").then(line("from").then(line("to").execute(executor(LineEdit::Move)))))
+        .then(literal("clear").execute(executor(LineEdit::Clear)))
+}
+
+/// `style` options shared by NPC and standalone holograms.
+fn hologram_style_subcommands<E: CommandExecutor + 'static>(
+    node: NonLeafNodeBuilder,
+    executor: fn(StyleOption) -> E,
+) -> NonLeafNodeBuilder {
+    let value = |option| argument("value", SimpleArgConsumer).execute(executor(option));
+    node.then(
+        literal("background")
+            .execute(executor(StyleOption::Background))
+            .then(value(StyleOption::Background).then(
+                argument("opacity", SimpleArgConsumer).execute(executor(StyleOption::Background)),
+            )),
+    )
+    .then(literal("billboard").then(value(StyleOption::Billboard)))
+    .then(literal("align").then(value(StyleOption::Alignment)))
+    .then(literal("width").then(value(StyleOption::LineWidth)))
+    .then(
+        literal("shadow")
+            .execute(executor(StyleOption::Shadow))
+            .then(value(StyleOption::Shadow)),
+    )
+    .then(literal("scale").then(value(StyleOption::Scale)))
+    .then(literal("offset").then(value(StyleOption::HeightOffset)))
+    .then(literal("spacing").then(value(StyleOption::LineSpacing)))
+}
+
+fn hologram_subcommands(node: NonLeafNodeBuilder) -> NonLeafNodeBuilder {
+    use hologram::{HologramEditExecutor, HologramStyleExecutor};
+
+    let node = node
+        .then(
+            literal("add")
+                .then(argument("text", MsgArgConsumer).execute(hologram::HologramAddExecutor)),
+        )
+        .then(literal("list").execute(hologram::HologramListExecutor))
+        .then(hologram_style_subcommands(
+            literal("style"),
+            HologramStyleExecutor,
+        ));
+    hologram_line_subcommands(node, HologramEditExecutor)
+}
+
 pub fn build_npc_command() -> CommandTree {
     CommandTree::new(["npc"], "Manage NPCs")
         .then(
-            literal("create")
-                .then(argument("name", SimpleArgConsumer).execute(create::CreateExecutor)),
+            literal("create").then(
+                argument("name", SimpleArgConsumer)
+                    .execute(create::CreateExecutor)
+                    .then(argument("key", SimpleArgConsumer).execute(create::CreateExecutor)),
+            ),
         )
         .then(
             literal("remove")
-                .then(argument("id", SimpleArgConsumer).execute(remove::RemoveExecutor)),
+                .then(argument("npc", NpcArgConsumer::new()).execute(remove::RemoveExecutor)),
         )
         .then(literal("list").execute(list::ListExecutor))
-        .then(literal("looknear").execute(looknear::LookNearExecutor))
+        .then(
+            literal("key").then(
+                argument("npc", NpcArgConsumer::new())
+                    .then(argument("key", SimpleArgConsumer).execute(key::KeyExecutor)),
+            ),
+        )
+        .then(
+            literal("rename").then(
+                argument("npc", NpcArgConsumer::new())
+                    .then(argument("name", MsgArgConsumer).execute(rename::RenameExecutor)),
+            ),
+        )
+        .then(
+            literal("looknear")
+                .execute(looknear::LookNearExecutor)
+                .then(argument("npc", NpcArgConsumer::new()).execute(looknear::LookNearExecutor)),
+        )
+        .then(with_target(literal("move"), move_subcommands))
+        .then(with_target(literal("rotate"), rotate_subcommands))
+        .then(literal("face").then(with_target(literal("me"), |node| {
+            node.execute(position::FaceMeExecutor)
+        })))
+        .then(
+            literal("tp")
+                .then(argument("npc", NpcArgConsumer::new()).execute(position::TeleportToExecutor)),
+        )
+        .then(with_target(literal("animate"), animate_subcommands))
+        .then(with_target(literal("scale"), |node| {
+            node.then(argument("scale", SimpleArgConsumer).execute(scale::ScaleExecutor))
+        }))
+        .then(with_target(literal("pose"), |node| {
+            node.then(argument("pose", SimpleArgConsumer).execute(pose::PoseExecutor))
+        }))
+        .then(with_target(literal("type"), |node| {
+            node.then(argument("type", SimpleArgConsumer).execute(entity::TypeExecutor))
+        }))
+        .then(with_target(literal("baby"), |node| {
+            node.execute(entity::BabyExecutor)
+        }))
+        .then(with_target(literal("villager"), villager_subcommands))
+        .then(with_target(literal("color"), |node| {
+            node.then(argument("color", SimpleArgConsumer).execute(entity::ColorExecutor))
+        }))
+        .then(with_target(literal("glow"), glow_subcommands))
+        .then(with_target(literal("nametag"), nametag_subcommands))
+        .then(with_target(literal("tab"), tab_subcommands))
+        .then(with_target(literal("layers"), layers_subcommands))
+        .then(with_target(literal("equip"), |node| {
+            node.then(argument("slot", SimpleArgConsumer).execute(equip::EquipExecutor))
+        }))
+        .then(with_target(literal("unequip"), |node| {
+            node.then(argument("slot", SimpleArgConsumer).execute(equip::UnequipExecutor))
+        }))
+        .then(
+            literal("reload")
+                .execute(reload::ReloadExecutor { force: false })
+                .then(literal("force").execute(reload::ReloadExecutor { force: true })),
+        )
+        .then(
+            literal("select")
+                .execute(select::SelectExecutor)
+                .then(argument("npc", NpcArgConsumer::new()).execute(select::SelectExecutor)),
+        )
+        .then(literal("deselect").execute(select::DeselectExecutor))
         .then(
             literal("server")
                 .then(
@@ -76,14 +406,67 @@ pub fn build_npc_command() -> CommandTree {
                 .then(literal("list").execute(server::ServerListExecutor))
                 .then(
                     literal("set").then(
-                        argument("name", SimpleArgConsumer).execute(server::ServerSetExecutor),
+                        argument("name", SimpleArgConsumer)
+                            .execute(server::ServerSetExecutor)
+                            .then(
+                                argument("npc", NpcArgConsumer::new())
+                                    .execute(server::ServerSetExecutor),
+                            ),
                     ),
                 ),
         )
+        .then(literal("storage").then(
+            literal("migrate").then(
+                argument("from", SimpleArgConsumer).then(
+                    argument("to", SimpleArgConsumer).execute(storage::StorageMigrateExecutor),
+                ),
+            ),
+        ))
+        .then(with_target(literal("hologram"), hologram_subcommands))
+}
+
+pub fn build_holo_command() -> CommandTree {
+    use holo::{
+        HoloCreateExecutor, HoloEditExecutor, HoloLinesExecutor, HoloListExecutor,
+        HoloMoveHereExecutor, HoloMoveToExecutor, HoloRemoveExecutor, HoloServerExecutor,
+        HoloStyleExecutor,
+    };
+
+    let target = || argument("hologram", HologramArgConsumer::new());
+    CommandTree::new(["holo"], "Manage standalone holograms")
+        .then(
+            literal("create").then(
+                argument("name", SimpleArgConsumer)
+                    .execute(HoloCreateExecutor)
+                    .then(argument("text", MsgArgConsumer).execute(HoloCreateExecutor)),
+            ),
+        )
+        .then(literal("remove").then(target().execute(HoloRemoveExecutor)))
+        .then(
+            literal("move").then(
+                target()
+                    .then(literal("here").execute(HoloMoveHereExecutor))
+                    .then(
+                        argument("position", Position3DArgumentConsumer)
+                            .execute(HoloMoveToExecutor),
+                    ),
+            ),
+        )
+        .then(literal("list").execute(HoloListExecutor))
+        .then(literal("lines").then(hologram_line_subcommands(
+            target().execute(HoloLinesExecutor).then(
+                literal("add").then(
+                    argument("text", MsgArgConsumer).execute(HoloEditExecutor(LineEdit::Add)),
+                ),
+            ),
+            HoloEditExecutor,
+        )))
+        .then(literal("style").then(hologram_style_subcommands(target(), HoloStyleExecutor)))
         .then(
-            literal("hologram").then(
-                literal("add")
-                    .then(argument("text", MsgArgConsumer).execute(hologram::HologramAddExecutor)),
+            literal("server").then(
+                target()
+                    .execute(HoloServerExecutor)
+                    .then(argument("server", SimpleArgConsumer).execute(HoloServerExecutor)),
             ),
         )
 }
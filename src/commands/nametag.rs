@@ -0,0 +1,105 @@
+use pumpkin::command::args::message::MsgArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{resolve_target, update_npc};
+use crate::npc::packets::update_team_for_player;
+use crate::npc::{Npc, NpcNametag};
+use crate::TRACKER;
+
+/// Edits the nametag and shows it to everyone viewing the NPC.
+async fn apply(npc: &Npc, edit: impl FnOnce(&mut NpcNametag)) -> Result<Npc, CommandError> {
+    let (_, npc) = update_npc(npc.id, |npc| {
+        edit(&mut npc.nametag);
+        Ok(())
+    })
+    .await?;
+
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    for p in tracker.viewers(npc.id).await {
+        update_team_for_player(&npc, &p).await;
+    }
+    Ok(npc)
+}
+
+pub struct NametagToggleExecutor;
+
+impl CommandExecutor for NametagToggleExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_target(sender, args).await?;
+            let npc = apply(&npc, |nametag| nametag.visible = !nametag.visible).await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Nametag {} for NPC '{}' (ID {})",
+                    if npc.nametag.visible {
+                        "shown"
+                    } else {
+                        "hidden"
+                    },
+                    npc.display_name,
+                    npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum NametagPart {
+    Prefix,
+    Suffix,
+}
+
+/// Sets or, without a text argument, clears the prefix or suffix.
+pub struct NametagTextExecutor(pub NametagPart);
+
+impl CommandExecutor for NametagTextExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let text = MsgArgConsumer::find_arg(args, "text").unwrap_or_default();
+
+            let npc = resolve_target(sender, args).await?;
+            let part = match self.0 {
+                NametagPart::Prefix => "prefix",
+                NametagPart::Suffix => "suffix",
+            };
+            let npc = apply(&npc, |nametag| match self.0 {
+                NametagPart::Prefix => nametag.prefix = text.clone(),
+                NametagPart::Suffix => nametag.suffix = text.clone(),
+            })
+            .await?;
+
+            let message = if text.is_empty() {
+                format!(
+                    "Cleared the nametag {part} of NPC '{}' (ID {})",
+                    npc.display_name, npc.id
+                )
+            } else {
+                format!(
+                    "Set the nametag {part} of NPC '{}' (ID {})",
+                    npc.display_name, npc.id
+                )
+            };
+            sender.send_message(TextComponent::text(message)).await;
+
+            Ok(1)
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::packets::update_pose_for_player;
+use crate::npc::pose::NpcPose;
+use crate::TRACKER;
+
+pub struct PoseExecutor;
+
+impl CommandExecutor for PoseExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let pose: NpcPose = SimpleArgConsumer::find_arg(args, "pose")
+                .map_err(|_| CommandError::InvalidConsumption(Some("pose".into())))?
+                .parse()
+                .map_err(failure)?;
+
+            let target = resolve_target(sender, args).await?;
+            let (before, npc) = update_npc(target.id, |npc| {
+                npc.pose = pose;
+                Ok(())
+            })
+            .await?;
+            let old = before.pose;
+
+            if old != pose {
+                let tracker = TRACKER.get().expect("NPC tracker not initialized");
+                for p in tracker.viewers(npc.id).await {
+                    update_pose_for_player(&npc, old, &p).await;
+                }
+            }
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) is now {pose}",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
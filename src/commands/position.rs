@@ -0,0 +1,234 @@
+use pumpkin::command::args::position_3d::Position3DArgumentConsumer;
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::args::NpcArgConsumer;
+use super::{failure, resolve_target, update_npc};
+use crate::npc::{find_world, player_world, Npc, NpcLocation};
+use crate::{REGISTRY, TRACKER};
+
+/// Edits the stored location and moves the NPC for everyone who can see it.
+async fn relocate(npc: &Npc, edit: impl FnOnce(&mut NpcLocation)) -> Result<Npc, CommandError> {
+    let (_, npc) = update_npc(npc.id, |npc| {
+        edit(&mut npc.location);
+        Ok(())
+    })
+    .await?;
+    TRACKER
+        .get()
+        .expect("NPC tracker not initialized")
+        .relocate_npc(&npc)
+        .await;
+    Ok(npc)
+}
+
+fn parse_angle(args: &ConsumedArgs<'_>, name: &str) -> Result<f32, CommandError> {
+    let value = SimpleArgConsumer::find_arg(args, name)
+        .map_err(|_| CommandError::InvalidConsumption(Some(name.into())))?;
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| failure(format!("Invalid {name}: {value}")))
+}
+
+pub struct MoveHereExecutor;
+
+impl CommandExecutor for MoveHereExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text("Only players can use this command"))
+                    .await;
+                return Ok(0);
+            };
+
+            let npc = resolve_target(sender, args).await?;
+            let pos = player.living_entity.entity.pos.load();
+            let world = player_world(player);
+            let npc = relocate(&npc, |location| {
+                location.world = world;
+                location.x = pos.x;
+                location.y = pos.y;
+                location.z = pos.z;
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Moved NPC '{}' (ID {}) to your position",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct MoveToExecutor;
+
+impl CommandExecutor for MoveToExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let pos = Position3DArgumentConsumer::find_arg(args, "position")?;
+
+            let npc = resolve_target(sender, args).await?;
+            let npc = relocate(&npc, |location| {
+                location.x = pos.x;
+                location.y = pos.y;
+                location.z = pos.z;
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Moved NPC '{}' (ID {}) to ({:.1}, {:.1}, {:.1})",
+                    npc.display_name, npc.id, npc.location.x, npc.location.y, npc.location.z
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct RotateExecutor;
+
+impl CommandExecutor for RotateExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let yaw = parse_angle(args, "yaw")?;
+            let pitch = parse_angle(args, "pitch")?.clamp(-90.0, 90.0);
+
+            let npc = resolve_target(sender, args).await?;
+            let npc = relocate(&npc, |location| {
+                location.yaw = yaw;
+                location.pitch = pitch;
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Rotated NPC '{}' (ID {}) to yaw {yaw:.1}, pitch {pitch:.1}",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct FaceMeExecutor;
+
+impl CommandExecutor for FaceMeExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text("Only players can use this command"))
+                    .await;
+                return Ok(0);
+            };
+
+            let npc = resolve_target(sender, args).await?;
+            let pos = player.living_entity.entity.pos.load();
+
+            let npc = relocate(&npc, |location| {
+                // Same maths as look-at-nearest
+                let dx = pos.x - location.x;
+                let dy = pos.y - location.y;
+                let dz = pos.z - location.z;
+                let horiz_dist = (dx * dx + dz * dz).sqrt();
+                location.yaw = (-dx).atan2(dz).to_degrees() as f32;
+                location.pitch = (-dy).atan2(horiz_dist).to_degrees() as f32;
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) now faces you",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct TeleportToExecutor;
+
+impl CommandExecutor for TeleportToExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let query = NpcArgConsumer::find_arg(args, "npc")
+                .map_err(|_| CommandError::InvalidConsumption(Some("npc".into())))?;
+
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text("Only players can use this command"))
+                    .await;
+                return Ok(0);
+            };
+
+            let registry = REGISTRY.get().expect("NPC registry not initialized");
+            let npc = registry
+                .find(query)
+                .await
+                .ok_or_else(|| failure(format!("No NPC found with key or ID '{query}'")))?;
+
+            let loc = &npc.location;
+            let position = pumpkin_util::math::vector3::Vector3::new(loc.x, loc.y, loc.z);
+            if player_world(player) == loc.world {
+                player.request_teleport(position, loc.yaw, loc.pitch).await;
+            } else {
+                let world = find_world(server, &loc.world)
+                    .ok_or_else(|| failure(format!("World {} is not loaded", loc.world)))?;
+                player
+                    .teleport_world(world, position, Some(loc.yaw), Some(loc.pitch))
+                    .await;
+            }
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Teleported to NPC '{}' (ID {})",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
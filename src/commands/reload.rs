@@ -0,0 +1,33 @@
+use pumpkin::command::args::ConsumedArgs;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+pub struct ReloadExecutor {
+    /// Drop in-game NPC edits that conflict with changes made on disk.
+    pub force: bool,
+}
+
+impl CommandExecutor for ReloadExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            match crate::reload::reload_all(self.force).await {
+                Ok(summary) => {
+                    sender.send_message(TextComponent::text(summary)).await;
+                    Ok(1)
+                }
+                Err(e) => {
+                    sender
+                        .send_message(TextComponent::text(format!("Reload failed: {e}")))
+                        .await;
+                    Ok(0)
+                }
+            }
+        })
+    }
+}
@@ -1,11 +1,11 @@
-use pumpkin::command::args::simple::SimpleArgConsumer;
 use pumpkin::command::args::{ConsumedArgs, FindArg};
 use pumpkin::command::dispatcher::CommandError;
 use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
 use pumpkin::server::Server;
 use pumpkin_util::text::TextComponent;
 
-use crate::REGISTRY;
+use super::args::NpcArgConsumer;
+use crate::{REGISTRY, TRACKER};
 
 pub struct RemoveExecutor;
 
@@ -13,36 +13,38 @@ impl CommandExecutor for RemoveExecutor {
     fn execute<'a>(
         &'a self,
         sender: &'a CommandSender,
-        server: &'a Server,
+        _server: &'a Server,
         args: &'a ConsumedArgs<'a>,
     ) -> CommandResult<'a> {
         Box::pin(async move {
-            let id_str = SimpleArgConsumer::find_arg(args, "id")
-                .map_err(|_| CommandError::InvalidConsumption(Some("id".into())))?;
-
-            let id: u32 = id_str.parse().map_err(|_| {
-                CommandError::CommandFailed(TextComponent::text(format!(
-                    "Invalid NPC ID: {id_str}"
-                )))
-            })?;
+            let query = NpcArgConsumer::find_arg(args, "npc")
+                .map_err(|_| CommandError::InvalidConsumption(Some("npc".into())))?;
 
             let registry = REGISTRY.get().expect("NPC registry not initialized");
 
-            let Some(npc) = registry.remove(id).await else {
+            let removed = match registry.find(query).await {
+                Some(npc) => registry.remove(npc.id).await,
+                None => None,
+            };
+            let Some(npc) = removed else {
                 sender
-                    .send_message(TextComponent::text(format!("No NPC found with ID {id}")))
+                    .send_message(TextComponent::text(format!(
+                        "No NPC found with key or ID '{query}'"
+                    )))
                     .await;
                 return Ok(0);
             };
 
-            for p in server.get_all_players() {
-                crate::npc::packets::despawn_npc_for_player(&npc, &p).await;
-            }
+            TRACKER
+                .get()
+                .expect("NPC tracker not initialized")
+                .forget_npc(&npc)
+                .await;
 
             sender
                 .send_message(TextComponent::text(format!(
                     "Removed NPC '{}' (ID {})",
-                    npc.name, npc.id
+                    npc.display_name, npc.id
                 )))
                 .await;
 
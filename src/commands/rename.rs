@@ -0,0 +1,51 @@
+use pumpkin::command::args::message::MsgArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{resolve_target, update_npc};
+use crate::npc::packets::{update_tab_for_player, update_team_for_player};
+use crate::TRACKER;
+
+pub struct RenameExecutor;
+
+impl CommandExecutor for RenameExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let name = MsgArgConsumer::find_arg(args, "name")
+                .map_err(|_| CommandError::InvalidConsumption(Some("name".into())))?;
+
+            let target = resolve_target(sender, args).await?;
+            let (before, npc) = update_npc(target.id, |npc| {
+                npc.display_name = name;
+                Ok(())
+            })
+            .await?;
+
+            // The display name shows up in the nametag and the tab list
+            let tracker = TRACKER.get().expect("NPC tracker not initialized");
+            for p in tracker.viewers(npc.id).await {
+                update_team_for_player(&npc, &p).await;
+                if npc.is_player() {
+                    update_tab_for_player(&before, &npc, &p).await;
+                }
+            }
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Renamed NPC '{}' (ID {}) to '{}'",
+                    before.display_name, npc.id, npc.display_name
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
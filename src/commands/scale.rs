@@ -0,0 +1,59 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::packets::update_scale_for_player;
+use crate::npc::SCALE_RANGE;
+use crate::TRACKER;
+
+pub struct ScaleExecutor;
+
+impl CommandExecutor for ScaleExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let value = SimpleArgConsumer::find_arg(args, "scale")
+                .map_err(|_| CommandError::InvalidConsumption(Some("scale".into())))?;
+            let scale = value
+                .parse::<f64>()
+                .ok()
+                .filter(|s| SCALE_RANGE.contains(s))
+                .ok_or_else(|| {
+                    failure(format!(
+                        "Invalid scale: {value} (expected {} to {})",
+                        SCALE_RANGE.start(),
+                        SCALE_RANGE.end()
+                    ))
+                })?;
+
+            let npc = resolve_target(sender, args).await?;
+            let (_, npc) = update_npc(npc.id, |npc| {
+                npc.scale = scale;
+                Ok(())
+            })
+            .await?;
+
+            let tracker = TRACKER.get().expect("NPC tracker not initialized");
+            for p in tracker.viewers(npc.id).await {
+                update_scale_for_player(&npc, &p).await;
+            }
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Scaled NPC '{}' (ID {}) to {scale}",
+                    npc.display_name, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
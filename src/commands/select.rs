@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use pumpkin::command::args::ConsumedArgs;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::entity::player::Player;
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, find_npc_in_crosshair};
+use crate::npc::packets::send_selection_glow;
+use crate::{REGISTRY, TRACKER};
+
+/// Removes the outline from a previously selected NPC, if the player can see it.
+async fn clear_glow(player: &Arc<Player>, npc_id: u32) {
+    let registry = REGISTRY.get().expect("NPC registry not initialized");
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    if !tracker.is_viewing(&player.gameprofile.id, npc_id).await {
+        return;
+    }
+    if let Some(npc) = registry.get(npc_id).await {
+        send_selection_glow(&npc, player, false).await;
+    }
+}
+
+pub struct SelectExecutor;
+
+impl CommandExecutor for SelectExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text("Only players can select NPCs"))
+                    .await;
+                return Ok(0);
+            };
+
+            let registry = REGISTRY.get().expect("NPC registry not initialized");
+            let tracker = TRACKER.get().expect("NPC tracker not initialized");
+
+            // Only an explicit NPC or the crosshair count here, not the current selection
+            let npc = if args.contains_key("npc") {
+                super::resolve_target(sender, args).await?
+            } else {
+                let npcs = registry.all().await;
+                let id = find_npc_in_crosshair(player, &npcs)
+                    .ok_or_else(|| failure("No NPC found in crosshair"))?;
+                npcs.into_iter()
+                    .find(|n| n.id == id)
+                    .ok_or_else(|| failure("NPC not found"))?
+            };
+
+            let uuid = player.gameprofile.id;
+            if let Some(previous) = registry.select(uuid, npc.id).await {
+                if previous != npc.id {
+                    clear_glow(player, previous).await;
+                }
+            }
+            if tracker.is_viewing(&uuid, npc.id).await {
+                send_selection_glow(&npc, player, true).await;
+            }
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Selected NPC '{}' (key {}, ID {})",
+                    npc.display_name, npc.key, npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+pub struct DeselectExecutor;
+
+impl CommandExecutor for DeselectExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        _args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let CommandSender::Player(player) = sender else {
+                sender
+                    .send_message(TextComponent::text("Only players can select NPCs"))
+                    .await;
+                return Ok(0);
+            };
+
+            let registry = REGISTRY.get().expect("NPC registry not initialized");
+            let Some(previous) = registry.deselect(&player.gameprofile.id).await else {
+                sender
+                    .send_message(TextComponent::text("No NPC selected"))
+                    .await;
+                return Ok(0);
+            };
+            clear_glow(player, previous).await;
+
+            sender
+                .send_message(TextComponent::text("Deselected NPC"))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
@@ -7,8 +7,7 @@ use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
 use pumpkin::server::Server;
 use pumpkin_util::text::TextComponent;
 
-use super::find_npc_in_crosshair;
-use crate::REGISTRY;
+use super::{resolve_target, update_npc};
 
 pub struct ServerAddExecutor;
 
@@ -135,41 +134,17 @@ impl CommandExecutor for ServerSetExecutor {
                 return Ok(0);
             }
 
-            let CommandSender::Player(player) = sender else {
-                sender
-                    .send_message(TextComponent::text("Only players can use this command"))
-                    .await;
-                return Ok(0);
-            };
-
-            let registry = REGISTRY.get().expect("NPC registry not initialized");
-            let npcs = registry.all().await;
-
-            if npcs.is_empty() {
-                sender
-                    .send_message(TextComponent::text("No NPCs exist"))
-                    .await;
-                return Ok(0);
-            }
-
-            let Some(npc_id) = find_npc_in_crosshair(player, &npcs) else {
-                sender
-                    .send_message(TextComponent::text("No NPC found in crosshair"))
-                    .await;
-                return Ok(0);
-            };
-
-            let Some(npc) = registry.set_server(npc_id, Some(server_name.clone())).await else {
-                sender
-                    .send_message(TextComponent::text("NPC not found"))
-                    .await;
-                return Ok(0);
-            };
+            let target = resolve_target(sender, args).await?;
+            let (_, npc) = update_npc(target.id, |npc| {
+                npc.server = Some(server_name.clone());
+                Ok(())
+            })
+            .await?;
 
             sender
                 .send_message(TextComponent::text(format!(
                     "Set server '{server_name}' on NPC '{}' (ID {})",
-                    npc.name, npc.id
+                    npc.display_name, npc.id
                 )))
                 .await;
 
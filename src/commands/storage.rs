@@ -0,0 +1,80 @@
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use crate::npc::storage::{self, StorageKind};
+use crate::REGISTRY;
+
+fn find_kind(args: &ConsumedArgs<'_>, name: &str) -> Result<StorageKind, CommandError> {
+    SimpleArgConsumer::find_arg(args, name)
+        .map_err(|_| CommandError::InvalidConsumption(Some(name.into())))?
+        .parse()
+        .map_err(|e: String| CommandError::CommandFailed(TextComponent::text(e)))
+}
+
+pub struct StorageMigrateExecutor;
+
+impl CommandExecutor for StorageMigrateExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let from = find_kind(args, "from")?;
+            let to = find_kind(args, "to")?;
+
+            if from == to {
+                sender
+                    .send_message(TextComponent::text(
+                        "Source and target storage must be different",
+                    ))
+                    .await;
+                return Ok(0);
+            }
+
+            let active = crate::config::config().storage;
+            if to == active {
+                sender
+                    .send_message(TextComponent::text(format!(
+                        "Cannot migrate into the active storage backend ({active})"
+                    )))
+                    .await;
+                return Ok(0);
+            }
+
+            // Make sure the active backend is up to date before copying from it
+            let registry = REGISTRY.get().expect("NPC registry not initialized");
+            if from == active {
+                registry.flush().await;
+            }
+
+            let source = storage::open(from)
+                .map_err(|e| CommandError::CommandFailed(TextComponent::text(e)))?;
+            let target = storage::open(to)
+                .map_err(|e| CommandError::CommandFailed(TextComponent::text(e)))?;
+
+            let npcs = storage::run(&source, |s| s.load())
+                .await
+                .map_err(|e| CommandError::CommandFailed(TextComponent::text(e)))?;
+            let count = npcs.len();
+            storage::run(&target, move |s| s.save_all(&npcs))
+                .await
+                .map_err(|e| CommandError::CommandFailed(TextComponent::text(e)))?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Copied {count} NPC(s) from {} to {}. Set storage = \"{to}\" in config.toml and restart to use it",
+                    source.describe(),
+                    target.describe()
+                )))
+                .await;
+
+            Ok(count as i32)
+        })
+    }
+}
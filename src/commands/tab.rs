@@ -0,0 +1,170 @@
+use pumpkin::command::args::message::MsgArgConsumer;
+use pumpkin::command::args::simple::SimpleArgConsumer;
+use pumpkin::command::args::{ConsumedArgs, FindArg};
+use pumpkin::command::dispatcher::CommandError;
+use pumpkin::command::{CommandExecutor, CommandResult, CommandSender};
+use pumpkin::server::Server;
+use pumpkin_util::text::TextComponent;
+
+use super::{failure, resolve_target, update_npc};
+use crate::npc::packets::update_tab_for_player;
+use crate::npc::tab::NpcTab;
+use crate::npc::{is_valid_key, Npc};
+use crate::TRACKER;
+
+/// Edits the tab entry and applies it for everyone viewing the NPC.
+async fn apply(
+    npc: &Npc,
+    edit: impl FnOnce(&mut NpcTab) -> Result<(), CommandError>,
+) -> Result<Npc, CommandError> {
+    let (before, updated) = update_npc(npc.id, |npc| edit(&mut npc.tab)).await?;
+
+    let tracker = TRACKER.get().expect("NPC tracker not initialized");
+    for p in tracker.viewers(updated.id).await {
+        update_tab_for_player(&before, &updated, &p).await;
+    }
+    Ok(updated)
+}
+
+async fn resolve_player_npc(
+    sender: &CommandSender,
+    args: &ConsumedArgs<'_>,
+) -> Result<Npc, CommandError> {
+    let npc = resolve_target(sender, args).await?;
+    if !npc.is_player() {
+        return Err(failure(format!(
+            "NPC '{}' (ID {}) is a {} and can't be listed",
+            npc.display_name, npc.id, npc.entity_type
+        )));
+    }
+    Ok(npc)
+}
+
+pub struct TabToggleExecutor;
+
+impl CommandExecutor for TabToggleExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_player_npc(sender, args).await?;
+            let npc = apply(&npc, |tab| {
+                tab.listed = !tab.listed;
+                Ok(())
+            })
+            .await?;
+
+            sender
+                .send_message(TextComponent::text(format!(
+                    "NPC '{}' (ID {}) is {} in the tab list",
+                    npc.display_name,
+                    npc.id,
+                    if npc.tab.listed {
+                        "now shown"
+                    } else {
+                        "no longer shown"
+                    }
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum TabOption {
+    Name,
+    Latency,
+    GameMode,
+    Order,
+    Group,
+}
+
+impl TabOption {
+    fn label(self) -> &'static str {
+        match self {
+            TabOption::Name => "tab name",
+            TabOption::Latency => "latency",
+            TabOption::GameMode => "game mode",
+            TabOption::Order => "tab order",
+            TabOption::Group => "tab group",
+        }
+    }
+
+    /// Applies the command's `value` argument. Name and group are cleared
+    /// when it is missing.
+    fn apply(self, tab: &mut NpcTab, args: &ConsumedArgs<'_>) -> Result<(), CommandError> {
+        let simple = || {
+            SimpleArgConsumer::find_arg(args, "value")
+                .map_err(|_| CommandError::InvalidConsumption(Some("value".into())))
+        };
+        match self {
+            TabOption::Name => {
+                tab.display_name = MsgArgConsumer::find_arg(args, "value").ok();
+            }
+            TabOption::Latency => {
+                let value = simple()?;
+                tab.latency = value
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|v| *v >= -1)
+                    .ok_or_else(|| failure(format!("Invalid latency: {value}")))?;
+            }
+            TabOption::GameMode => {
+                tab.game_mode = simple()?.parse().map_err(failure)?;
+            }
+            TabOption::Order => {
+                let value = simple()?;
+                tab.order = value
+                    .parse()
+                    .map_err(|_| failure(format!("Invalid order: {value}")))?;
+            }
+            TabOption::Group => {
+                let group = SimpleArgConsumer::find_arg(args, "value").ok();
+                if let Some(group) = group.filter(|g| !is_valid_key(g)) {
+                    return Err(failure(format!(
+                        "Invalid group '{group}': use lowercase letters, digits, _ and -"
+                    )));
+                }
+                tab.group = group.map(str::to_string);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct TabSetExecutor(pub TabOption);
+
+impl CommandExecutor for TabSetExecutor {
+    fn execute<'a>(
+        &'a self,
+        sender: &'a CommandSender,
+        _server: &'a Server,
+        args: &'a ConsumedArgs<'a>,
+    ) -> CommandResult<'a> {
+        Box::pin(async move {
+            let npc = resolve_player_npc(sender, args).await?;
+            let npc = apply(&npc, |tab| self.0.apply(tab, args)).await?;
+
+            let hint = if npc.tab.listed {
+                ""
+            } else {
+                " (not listed, use /npc tab to show it)"
+            };
+            sender
+                .send_message(TextComponent::text(format!(
+                    "Updated the {} of NPC '{}' (ID {}){hint}",
+                    self.0.label(),
+                    npc.display_name,
+                    npc.id
+                )))
+                .await;
+
+            Ok(1)
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::npc::storage::StorageKind;
+use crate::DATA_FOLDER;
+
+const CONFIG_FILE: &str = "config.toml";
+
+static CONFIG: OnceLock<PluginConfig> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginConfig {
+    /// Backend NPCs are persisted to.
+    pub storage: StorageKind,
+    /// Horizontal distance in blocks within which players see an NPC.
+    pub view_distance: f64,
+    /// When set, NPCs are shown to players within this many chunks instead of
+    /// using `view_distance`.
+    pub view_chunk_radius: Option<u32>,
+    /// How long to wait after an edit before writing NPCs to disk, so bursts
+    /// of edits end up in a single write.
+    pub save_delay_ms: u64,
+    /// Number of rotating `.bak.N` copies kept of file-based NPC storage.
+    pub backups: usize,
+    /// Reload `npcs.toml`/`npcs.json`, `holograms.toml` and `servers.toml`
+    /// automatically when they are edited on disk.
+    pub watch_files: bool,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            storage: StorageKind::Toml,
+            view_distance: 48.0,
+            view_chunk_radius: None,
+            save_delay_ms: 500,
+            backups: 3,
+            watch_files: false,
+        }
+    }
+}
+
+pub fn config() -> &'static PluginConfig {
+    CONFIG.get_or_init(PluginConfig::default)
+}
+
+/// Reads `config.toml`, writing the defaults out first if it doesn't exist yet.
+pub fn load() -> &'static PluginConfig {
+    CONFIG.get_or_init(|| {
+        let path = DATA_FOLDER
+            .get()
+            .expect("Data folder not initialized")
+            .join(CONFIG_FILE);
+
+        if !path.exists() {
+            let config = PluginConfig::default();
+            match toml::to_string_pretty(&config) {
+                Ok(contents) => {
+                    if let Err(e) = std::fs::write(&path, contents) {
+                        log::error!("Failed to write {CONFIG_FILE}: {e}");
+                    }
+                }
+                Err(e) => log::error!("Failed to serialize default config: {e}"),
+            }
+            return config;
+        }
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to read {CONFIG_FILE}: {e}");
+                return PluginConfig::default();
+            }
+        };
+        match toml::from_str(&contents) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to parse {CONFIG_FILE}, using defaults: {e}");
+                PluginConfig::default()
+            }
+        }
+    })
+}
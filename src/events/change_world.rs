@@ -0,0 +1,37 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use pumpkin::plugin::api::events::player::player_change_world::PlayerChangeWorldEvent;
+use pumpkin::plugin::EventHandler;
+use pumpkin::server::Server;
+
+use crate::npc::world_key;
+use crate::TRACKER;
+
+pub struct ChangeWorldHandler;
+
+impl EventHandler<PlayerChangeWorldEvent> for ChangeWorldHandler {
+    fn handle<'a>(
+        &'a self,
+        _server: &'a Arc<Server>,
+        event: &'a PlayerChangeWorldEvent,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let tracker = match TRACKER.get() {
+                Some(t) => t,
+                None => return,
+            };
+
+            let next = world_key(&event.new_world);
+            if world_key(&event.previous_world) == next {
+                return;
+            }
+
+            // Entities vanish with the respawn, but tab list entries don't.
+            // The event fires before the respawn packet is sent, so the
+            // new world's NPCs wait until the client has loaded it.
+            tracker.start_respawn(&event.player, next).await;
+        })
+    }
+}
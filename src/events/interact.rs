@@ -7,7 +7,7 @@ use pumpkin::plugin::EventHandler;
 use pumpkin::server::Server;
 use pumpkin_protocol::java::server::play::ActionType;
 
-use crate::REGISTRY;
+use crate::{REGISTRY, TRACKER};
 
 pub struct InteractHandler;
 
@@ -18,7 +18,8 @@ impl EventHandler<PlayerInteractUnknownEntityEvent> for InteractHandler {
         event: &'a PlayerInteractUnknownEntityEvent,
     ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
         Box::pin(async move {
-            if event.action != ActionType::Interact {
+            // Each click also arrives as an InteractAt
+            if event.action == ActionType::InteractAt {
                 return;
             }
 
@@ -32,6 +33,14 @@ impl EventHandler<PlayerInteractUnknownEntityEvent> for InteractHandler {
                 None => return,
             };
 
+            if let (Some(animation), Some(tracker)) = (npc.animations.on_click, TRACKER.get()) {
+                tracker.animate(&npc, animation).await;
+            }
+
+            if event.action != ActionType::Interact {
+                return;
+            }
+
             let server_name = match &npc.server {
                 Some(s) => s,
                 None => return,
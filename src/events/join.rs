@@ -6,7 +6,7 @@ use pumpkin::plugin::api::events::player::player_join::PlayerJoinEvent;
 use pumpkin::plugin::EventHandler;
 use pumpkin::server::Server;
 
-use crate::REGISTRY;
+use crate::TRACKER;
 
 pub struct JoinHandler;
 
@@ -17,15 +17,13 @@ impl EventHandler<PlayerJoinEvent> for JoinHandler {
         event: &'a PlayerJoinEvent,
     ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
         Box::pin(async move {
-            let registry = match REGISTRY.get() {
-                Some(r) => r,
+            let tracker = match TRACKER.get() {
+                Some(t) => t,
                 None => return,
             };
 
-            let npcs = registry.all().await;
-            for npc in &npcs {
-                crate::npc::packets::spawn_npc_for_player(npc, &event.player).await;
-            }
+            let pos = event.player.living_entity.entity.pos.load();
+            tracker.update_player(&event.player, &pos).await;
         })
     }
 }
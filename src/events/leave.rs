@@ -0,0 +1,30 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use pumpkin::plugin::api::events::player::player_leave::PlayerLeaveEvent;
+use pumpkin::plugin::EventHandler;
+use pumpkin::server::Server;
+
+use crate::{REGISTRY, TRACKER};
+
+pub struct LeaveHandler;
+
+impl EventHandler<PlayerLeaveEvent> for LeaveHandler {
+    fn handle<'a>(
+        &'a self,
+        _server: &'a Arc<Server>,
+        event: &'a PlayerLeaveEvent,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let (registry, tracker) = match (REGISTRY.get(), TRACKER.get()) {
+                (Some(r), Some(t)) => (r, t),
+                _ => return,
+            };
+
+            let uuid = event.player.gameprofile.id;
+            tracker.remove_player(&uuid).await;
+            registry.deselect(&uuid).await;
+        })
+    }
+}
@@ -1,3 +1,6 @@
+pub mod change_world;
 pub mod interact;
 pub mod join;
+pub mod leave;
 pub mod player_move;
+pub mod teleport;
@@ -6,7 +6,7 @@ use pumpkin::plugin::api::events::player::player_move::PlayerMoveEvent;
 use pumpkin::plugin::EventHandler;
 use pumpkin::server::Server;
 
-use crate::REGISTRY;
+use crate::{REGISTRY, TRACKER};
 
 pub struct MoveHandler;
 
@@ -17,17 +17,26 @@ impl EventHandler<PlayerMoveEvent> for MoveHandler {
         event: &'a PlayerMoveEvent,
     ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
         Box::pin(async move {
-            let registry = match REGISTRY.get() {
-                Some(r) => r,
-                None => return,
+            let (registry, tracker) = match (REGISTRY.get(), TRACKER.get()) {
+                (Some(r), Some(t)) => (r, t),
+                _ => return,
             };
 
+            tracker.confirm_world(&event.player).await;
+            tracker.update_player(&event.player, &event.to).await;
+            tracker.check_approach(&event.player, &event.to).await;
+
             let npcs = registry.look_at_nearest_npcs().await;
             if npcs.is_empty() {
                 return;
             }
 
+            let player_uuid = event.player.gameprofile.id;
             for npc in &npcs {
+                if !tracker.is_viewing(&player_uuid, npc.id).await {
+                    continue;
+                }
+
                 let dx = event.to.x - npc.location.x;
                 let dz = event.to.z - npc.location.z;
                 let dist_sq = dx * dx + dz * dz;
@@ -0,0 +1,28 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use pumpkin::plugin::api::events::player::player_teleport::PlayerTeleportEvent;
+use pumpkin::plugin::EventHandler;
+use pumpkin::server::Server;
+
+use crate::TRACKER;
+
+pub struct TeleportHandler;
+
+impl EventHandler<PlayerTeleportEvent> for TeleportHandler {
+    fn handle<'a>(
+        &'a self,
+        _server: &'a Arc<Server>,
+        event: &'a PlayerTeleportEvent,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let tracker = match TRACKER.get() {
+                Some(t) => t,
+                None => return,
+            };
+
+            tracker.update_player(&event.player, &event.to).await;
+        })
+    }
+}
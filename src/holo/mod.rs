@@ -0,0 +1,82 @@
+//! Holograms that float on their own instead of above an NPC, e.g. a welcome
+//! sign over spawn. They are drawn by the same renderer as NPC holograms.
+
+use pumpkin::net::java::JavaClient;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::npc::hologram::{HologramAnchor, HologramStyle};
+use crate::npc::{next_entity_id, HologramLine, NpcLocation};
+
+pub mod registry;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Hologram {
+    pub id: u32,
+    /// Unique handle used in commands, following the same rules as NPC keys.
+    pub name: String,
+    /// The bottom line floats the style's height offset above this.
+    pub location: NpcLocation,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<HologramLine>,
+    #[serde(default, skip_serializing_if = "crate::npc::is_default")]
+    pub style: HologramStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(skip)]
+    pub entity_id: i32,
+}
+
+impl Hologram {
+    pub fn new(id: u32, name: String, location: NpcLocation, lines: Vec<String>) -> Self {
+        Self {
+            id,
+            name,
+            location,
+            lines: lines.into_iter().map(HologramLine::new).collect(),
+            style: HologramStyle::default(),
+            server: None,
+            entity_id: next_entity_id(),
+        }
+    }
+
+    pub fn init_runtime_fields(&mut self) {
+        self.entity_id = next_entity_id();
+        for line in &mut self.lines {
+            line.init_runtime_fields();
+        }
+    }
+}
+
+impl HologramAnchor for Hologram {
+    fn location(&self) -> &NpcLocation {
+        &self.location
+    }
+
+    fn base_height(&self, _java: &JavaClient) -> f64 {
+        0.0
+    }
+
+    fn lines(&self) -> &[HologramLine] {
+        &self.lines
+    }
+
+    fn style(&self) -> &HologramStyle {
+        &self.style
+    }
+
+    fn display_entity_id(&self) -> i32 {
+        self.entity_id
+    }
+
+    fn display_uuid(&self) -> Uuid {
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_DNS,
+            format!("hologram:standalone:{}", self.id).as_bytes(),
+        )
+    }
+
+    fn server(&self) -> Option<&str> {
+        self.server.as_deref()
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use super::Hologram;
+use crate::npc::{is_valid_key, HologramLine, NpcLocation};
+use crate::{persist, DATA_FOLDER};
+
+const HOLOGRAMS_FILE: &str = "holograms.toml";
+
+#[derive(Default, Serialize, Deserialize)]
+struct HologramsConfig {
+    #[serde(default)]
+    holograms: Vec<Hologram>,
+}
+
+/// Difference between the in-memory holograms and the file after a reload.
+#[derive(Default)]
+pub struct HologramDiff {
+    pub added: Vec<Hologram>,
+    pub removed: Vec<Hologram>,
+    /// Old and new version of each hologram whose stored fields changed.
+    pub changed: Vec<(Hologram, Hologram)>,
+}
+
+/// Compares only the persisted fields, ignoring runtime IDs.
+fn same_stored(a: &Hologram, b: &Hologram) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn path() -> PathBuf {
+    DATA_FOLDER
+        .get()
+        .expect("Data folder not initialized")
+        .join(HOLOGRAMS_FILE)
+}
+
+fn read_holograms() -> Result<Vec<Hologram>, String> {
+    let path = path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {HOLOGRAMS_FILE}: {e}"))?;
+    let config: HologramsConfig =
+        toml::from_str(&contents).map_err(|e| format!("Failed to parse {HOLOGRAMS_FILE}: {e}"))?;
+    Ok(config.holograms)
+}
+
+/// Standalone holograms, kept in `holograms.toml`. Holograms are few and
+/// rarely edited, so every edit rewrites the file right away.
+pub struct HologramRegistry {
+    holograms: RwLock<HashMap<u32, Hologram>>,
+    next_id: AtomicU32,
+    /// Set when the file on disk couldn't be read, so it is never overwritten.
+    read_only: AtomicBool,
+}
+
+impl HologramRegistry {
+    pub fn new() -> Self {
+        Self {
+            holograms: RwLock::new(HashMap::new()),
+            next_id: AtomicU32::new(1),
+            read_only: AtomicBool::new(false),
+        }
+    }
+
+    pub async fn load(&self) -> usize {
+        let loaded = match read_holograms() {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                log::error!("{e}; holograms will not be saved until it is fixed");
+                self.read_only.store(true, Ordering::Relaxed);
+                return 0;
+            }
+        };
+
+        let count = loaded.len();
+        let mut holograms = self.holograms.write().await;
+        for mut hologram in loaded {
+            hologram.init_runtime_fields();
+            self.next_id.fetch_max(hologram.id + 1, Ordering::Relaxed);
+            holograms.insert(hologram.id, hologram);
+        }
+        count
+    }
+
+    /// Re-reads `holograms.toml`, keeping the runtime state of holograms that
+    /// didn't change. On failure the current holograms are left as they are.
+    pub async fn reload(&self) -> Result<HologramDiff, String> {
+        let loaded = read_holograms()?;
+        self.read_only.store(false, Ordering::Relaxed);
+
+        let mut diff = HologramDiff::default();
+        let mut holograms = self.holograms.write().await;
+        let mut old = std::mem::take(&mut *holograms);
+
+        for mut hologram in loaded {
+            match old.remove(&hologram.id) {
+                Some(existing) if same_stored(&existing, &hologram) => {
+                    holograms.insert(existing.id, existing);
+                }
+                Some(existing) => {
+                    hologram.init_runtime_fields();
+                    holograms.insert(hologram.id, hologram.clone());
+                    diff.changed.push((existing, hologram));
+                }
+                None => {
+                    hologram.init_runtime_fields();
+                    holograms.insert(hologram.id, hologram.clone());
+                    diff.added.push(hologram);
+                }
+            }
+        }
+        diff.removed = old.into_values().collect();
+
+        if let Some(max_id) = holograms.keys().max() {
+            self.next_id.fetch_max(max_id + 1, Ordering::Relaxed);
+        }
+        Ok(diff)
+    }
+
+    fn save(&self, holograms: &HashMap<u32, Hologram>) {
+        if self.read_only.load(Ordering::Relaxed) {
+            log::error!("Not saving holograms: {HOLOGRAMS_FILE} couldn't be read");
+            return;
+        }
+
+        let mut sorted: Vec<Hologram> = holograms.values().cloned().collect();
+        sorted.sort_by_key(|h| h.id);
+        let config = HologramsConfig { holograms: sorted };
+        let contents = match toml::to_string_pretty(&config) {
+            Ok(contents) => contents,
+            Err(e) => {
+                log::error!("Failed to serialize holograms: {e}");
+                return;
+            }
+        };
+
+        let backups = crate::config::config().backups;
+        if let Err(e) = persist::write_atomic(&path(), &contents, backups) {
+            log::error!("Failed to write {HOLOGRAMS_FILE}: {e}");
+        }
+    }
+
+    pub async fn get(&self, id: u32) -> Option<Hologram> {
+        self.holograms.read().await.get(&id).cloned()
+    }
+
+    /// Looks a hologram up by name, falling back to a numeric ID.
+    pub async fn find(&self, query: &str) -> Option<Hologram> {
+        let holograms = self.holograms.read().await;
+        if let Some(hologram) = holograms
+            .values()
+            .find(|h| h.name.eq_ignore_ascii_case(query))
+        {
+            return Some(hologram.clone());
+        }
+        let id: u32 = query.strip_prefix('#').unwrap_or(query).parse().ok()?;
+        holograms.get(&id).cloned()
+    }
+
+    pub async fn all(&self) -> Vec<Hologram> {
+        self.holograms.read().await.values().cloned().collect()
+    }
+
+    /// Calls `f` with every hologram without cloning them.
+    pub async fn for_each(&self, f: impl FnMut(&Hologram)) {
+        self.holograms.read().await.values().for_each(f);
+    }
+
+    /// Holograms with at least one animated line.
+    pub async fn animated(&self) -> Vec<Hologram> {
+        self.holograms
+            .read()
+            .await
+            .values()
+            .filter(|h| h.lines.iter().any(|l| l.animation.is_some()))
+            .cloned()
+            .collect()
+    }
+
+    /// Holograms with at least one spinning item or block line.
+    pub async fn spinning(&self) -> Vec<Hologram> {
+        self.holograms
+            .read()
+            .await
+            .values()
+            .filter(|h| h.lines.iter().any(HologramLine::spins))
+            .cloned()
+            .collect()
+    }
+
+    pub async fn create(
+        &self,
+        name: String,
+        location: NpcLocation,
+        lines: Vec<String>,
+    ) -> Result<Hologram, String> {
+        if !is_valid_key(&name) {
+            return Err(format!(
+                "Invalid name '{name}': use up to 32 lowercase letters, digits, '_' or '-', not only digits"
+            ));
+        }
+
+        let mut holograms = self.holograms.write().await;
+        if holograms.values().any(|h| h.name == name) {
+            return Err(format!("A hologram named '{name}' already exists"));
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let hologram = Hologram::new(id, name, location, lines);
+        holograms.insert(id, hologram.clone());
+        self.save(&holograms);
+        Ok(hologram)
+    }
+
+    pub async fn remove(&self, id: u32) -> Option<Hologram> {
+        let mut holograms = self.holograms.write().await;
+        let hologram = holograms.remove(&id)?;
+        self.save(&holograms);
+        Some(hologram)
+    }
+
+    /// Edits a hologram while holding the write lock, so edits made at the
+    /// same time can't undo each other. If `f` fails the hologram is left as
+    /// it was. Returns the hologram before and after the edit.
+    pub async fn update<E>(
+        &self,
+        id: u32,
+        f: impl FnOnce(&mut Hologram) -> Result<(), E>,
+    ) -> Option<Result<(Hologram, Hologram), E>> {
+        let mut holograms = self.holograms.write().await;
+        let hologram = holograms.get_mut(&id)?;
+        let mut after = hologram.clone();
+        if let Err(e) = f(&mut after) {
+            return Some(Err(e));
+        }
+        let before = std::mem::replace(hologram, after.clone());
+        self.save(&holograms);
+        Some(Ok((before, after)))
+    }
+}
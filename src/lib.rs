@@ -9,13 +9,22 @@ use pumpkin_api_macros::{plugin_impl, plugin_method};
 use pumpkin_util::permission::{Permission, PermissionDefault, PermissionLvl};
 
 mod commands;
+mod config;
 mod events;
+mod holo;
 mod npc;
+mod persist;
+mod reload;
 mod status;
+mod text;
 
+use holo::registry::HologramRegistry;
 use npc::registry::NpcRegistry;
+use npc::tracker::ViewTracker;
 
 pub(crate) static REGISTRY: OnceLock<Arc<NpcRegistry>> = OnceLock::new();
+pub(crate) static HOLOGRAMS: OnceLock<Arc<HologramRegistry>> = OnceLock::new();
+pub(crate) static TRACKER: OnceLock<Arc<ViewTracker>> = OnceLock::new();
 pub(crate) static CONTEXT: OnceLock<Arc<Context>> = OnceLock::new();
 pub(crate) static DATA_FOLDER: OnceLock<PathBuf> = OnceLock::new();
 
@@ -29,15 +38,32 @@ async fn on_load(&mut self, server: Arc<Context>) -> Result<(), String> {
     DATA_FOLDER
         .set(data_folder)
         .map_err(|_| "Data folder already initialized")?;
+    let config = config::load();
 
-    let registry = Arc::new(NpcRegistry::new());
+    let storage = npc::storage::open(config.storage)?;
+    let registry = Arc::new(NpcRegistry::new(storage));
     let loaded = registry.load().await;
     if loaded > 0 {
-        log::info!("Loaded {loaded} NPC(s) from npcs.toml");
+        log::info!(
+            "Loaded {loaded} NPC(s) from {}",
+            registry.storage().describe()
+        );
     }
+    registry.start_writer();
     REGISTRY
         .set(registry)
         .map_err(|_| "Registry already initialized")?;
+    let holograms = Arc::new(HologramRegistry::new());
+    let loaded = holograms.load().await;
+    if loaded > 0 {
+        log::info!("Loaded {loaded} hologram(s)");
+    }
+    HOLOGRAMS
+        .set(holograms)
+        .map_err(|_| "Hologram registry already initialized")?;
+    TRACKER
+        .set(Arc::new(ViewTracker::new()))
+        .map_err(|_| "Tracker already initialized")?;
     CONTEXT
         .set(Arc::clone(&server))
         .map_err(|_| "Context already initialized")?;
@@ -53,6 +79,8 @@ async fn on_load(&mut self, server: Arc<Context>) -> Result<(), String> {
 
     let tree = commands::build_npc_command();
     server.register_command(tree, "npcs:npc").await;
+    let tree = commands::build_holo_command();
+    server.register_command(tree, "npcs:npc").await;
 
     server
         .register_event(
@@ -68,6 +96,27 @@ async fn on_load(&mut self, server: Arc<Context>) -> Result<(), String> {
             false,
         )
         .await;
+    server
+        .register_event(
+            Arc::new(events::teleport::TeleportHandler),
+            EventPriority::Normal,
+            false,
+        )
+        .await;
+    server
+        .register_event(
+            Arc::new(events::leave::LeaveHandler),
+            EventPriority::Normal,
+            false,
+        )
+        .await;
+    server
+        .register_event(
+            Arc::new(events::change_world::ChangeWorldHandler),
+            EventPriority::Normal,
+            false,
+        )
+        .await;
     server
         .register_event(
             Arc::new(events::interact::InteractHandler),
@@ -77,12 +126,25 @@ async fn on_load(&mut self, server: Arc<Context>) -> Result<(), String> {
         .await;
 
     status::start_status_task();
+    npc::hologram::start_spin_task();
+    npc::animator::start_animator();
+    if config.watch_files {
+        reload::start_watcher();
+    }
 
     log::info!("NPCs plugin loaded — /npc command available");
 
     Ok(())
 }
 
+#[plugin_method]
+async fn on_unload(&mut self, _server: Arc<Context>) -> Result<(), String> {
+    if let Some(registry) = REGISTRY.get() {
+        registry.flush().await;
+    }
+    Ok(())
+}
+
 #[plugin_impl]
 pub struct Npcs {}
 
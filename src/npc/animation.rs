@@ -0,0 +1,89 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NpcAnimation {
+    Swing,
+    SwingOffhand,
+    Hurt,
+    Crit,
+    /// Leave bed; only visible on sleeping NPCs.
+    Wake,
+}
+
+impl NpcAnimation {
+    pub const ALL: [NpcAnimation; 5] = [
+        NpcAnimation::Swing,
+        NpcAnimation::SwingOffhand,
+        NpcAnimation::Hurt,
+        NpcAnimation::Crit,
+        NpcAnimation::Wake,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NpcAnimation::Swing => "swing",
+            NpcAnimation::SwingOffhand => "swing_offhand",
+            NpcAnimation::Hurt => "hurt",
+            NpcAnimation::Crit => "crit",
+            NpcAnimation::Wake => "wake",
+        }
+    }
+
+    /// Id for the entity animation packet. Hurt has its own packet.
+    pub fn animation_id(self) -> Option<u8> {
+        match self {
+            NpcAnimation::Swing => Some(0),
+            NpcAnimation::Wake => Some(2),
+            NpcAnimation::SwingOffhand => Some(3),
+            NpcAnimation::Crit => Some(4),
+            NpcAnimation::Hurt => None,
+        }
+    }
+}
+
+impl fmt::Display for NpcAnimation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NpcAnimation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NpcAnimation::ALL
+            .into_iter()
+            .find(|a| a.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown animation '{s}' (expected swing, swing_offhand, hurt, crit or wake)"
+                )
+            })
+    }
+}
+
+/// Animations an NPC plays by itself.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoAnimations {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_click: Option<NpcAnimation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_approach: Option<NpcAnimation>,
+    /// Distance at which a player counts as approaching.
+    pub approach_distance: f64,
+}
+
+impl Default for AutoAnimations {
+    fn default() -> Self {
+        Self {
+            on_click: None,
+            on_approach: None,
+            approach_distance: 4.0,
+        }
+    }
+}
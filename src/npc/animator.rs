@@ -0,0 +1,308 @@
+//! Animated hologram lines. A line written as
+//!
+//! - `{frames:<ticks>:<frame>|<frame>|...}` cycles through the frames,
+//! - `{typewriter:<ticks>:<text>}` types the text out one character at a time,
+//! - `{marquee:<ticks>:<width>:<text>}` scrolls the text through a window
+//!   `width` characters wide,
+//! - `{pulse:<ticks>:<color>,<color>,...:<text>}` fades the text from color to
+//!   color,
+//!
+//! moves on to its next frame every `<ticks>` game ticks. Lines are parsed
+//! once when they are set or loaded. The animator counts ticks and resends
+//! the lines whose frame changed to the players viewing them.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use super::hologram::{line_text, update_lines_for_player, HologramAnchor};
+use crate::{text, HOLOGRAMS, REGISTRY, TRACKER};
+
+const TICK: Duration = Duration::from_millis(50);
+
+/// Spaces between the end of a marquee text and its next start.
+const MARQUEE_GAP: usize = 4;
+
+/// Frames a pulse takes to fade from one color to the next.
+const PULSE_STEPS: u64 = 10;
+
+/// Ticks since the animator started. Every line reads its frame from this,
+/// so lines with the same interval stay in step.
+static TICK_COUNT: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug)]
+enum Effect {
+    Frames(Vec<String>),
+    Typewriter(String),
+    Marquee { width: usize, text: String },
+    Pulse { colors: Vec<u32>, text: String },
+}
+
+/// A parsed animated line, kept on its line so it is only parsed once.
+#[derive(Debug)]
+pub struct LineAnimation {
+    interval: u64,
+    effect: Effect,
+}
+
+impl LineAnimation {
+    /// Parses an animated line. `Ok(None)` means the line isn't animated.
+    pub fn parse(text: &str) -> Result<Option<Self>, String> {
+        let line = text.trim();
+        let Some(body) = line.strip_prefix('{').and_then(|t| t.strip_suffix('}')) else {
+            return Ok(None);
+        };
+        let Some((kind, rest)) = body.split_once(':') else {
+            return Ok(None);
+        };
+        if !matches!(kind, "frames" | "typewriter" | "marquee" | "pulse") {
+            return Ok(None);
+        }
+
+        let invalid = || format!("Invalid line '{line}': see the README for the {kind} syntax");
+        let (interval, rest) = rest.split_once(':').ok_or_else(invalid)?;
+        let interval = interval
+            .parse::<u64>()
+            .ok()
+            .filter(|ticks| *ticks > 0)
+            .ok_or_else(|| format!("Invalid interval '{interval}' (expected a number of ticks)"))?;
+
+        let effect = match kind {
+            "frames" => Effect::Frames(rest.split('|').map(str::to_string).collect()),
+            "typewriter" => Effect::Typewriter(rest.to_string()),
+            "marquee" => {
+                let (width, text) = rest.split_once(':').ok_or_else(invalid)?;
+                let width = width
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|width| *width > 0)
+                    .ok_or_else(|| format!("Invalid marquee width '{width}'"))?;
+                Effect::Marquee {
+                    width,
+                    text: text.to_string(),
+                }
+            }
+            _ => {
+                let (colors, text) = rest.split_once(':').ok_or_else(invalid)?;
+                let colors = colors
+                    .split(',')
+                    .map(|color| {
+                        text::parse_color(color.trim())
+                            .ok_or_else(|| format!("Unknown color '{color}'"))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Effect::Pulse {
+                    colors,
+                    text: text.to_string(),
+                }
+            }
+        };
+        Ok(Some(Self { interval, effect }))
+    }
+
+    /// The frame shown at `tick`. `resolve` fills in the placeholders before
+    /// the text is cut up, so a placeholder is never cut in half.
+    fn frame_at(&self, tick: u64, resolve: impl Fn(&str) -> String) -> String {
+        let index = tick / self.interval;
+        match &self.effect {
+            Effect::Frames(frames) => resolve(&frames[(index % frames.len() as u64) as usize]),
+            Effect::Typewriter(text) => {
+                let text = resolve(text);
+                let len = text::visible_len(&text);
+                // Typed out, then shown in full for as long as typing took
+                let frame = index % (2 * len as u64).max(1);
+                text::slice(&text, 0, (frame as usize + 1).min(len))
+            }
+            Effect::Marquee { width, text } => {
+                let content = format!("{}{}", resolve(text), " ".repeat(MARQUEE_GAP));
+                let total = text::visible_len(&content);
+                let start = (index % total as u64) as usize;
+                let width = (*width).min(total);
+                let mut frame = text::slice(&content, start, width);
+                let wrapped = (start + width).saturating_sub(total);
+                if wrapped > 0 {
+                    frame.push_str("<reset>");
+                    frame.push_str(&text::slice(&content, 0, wrapped));
+                }
+                frame
+            }
+            Effect::Pulse { colors, text } => {
+                let index = index % (colors.len() as u64 * PULSE_STEPS);
+                let from = (index / PULSE_STEPS) as usize;
+                let to = (from + 1) % colors.len();
+                let t = (index % PULSE_STEPS) as f32 / PULSE_STEPS as f32;
+                let color = text::lerp(colors[from], colors[to], t);
+                format!("<#{color:06x}>{}", resolve(text))
+            }
+        }
+    }
+
+    /// The frame shown right now.
+    pub fn current_frame(&self, resolve: impl Fn(&str) -> String) -> String {
+        self.frame_at(TICK_COUNT.load(Ordering::Relaxed), resolve)
+    }
+}
+
+/// Checks the syntax of an animated line. Other lines always pass.
+pub fn check_line(text: &str) -> Result<(), String> {
+    LineAnimation::parse(text).map(|_| ())
+}
+
+/// Renders the animated lines of `holo` that may step at `tick` and returns
+/// the entity IDs of those whose frame differs from the one last sent.
+fn advance(
+    holo: &impl HologramAnchor,
+    tick: u64,
+    sent: &mut HashMap<i32, String>,
+    seen: &mut HashSet<i32>,
+) -> HashSet<i32> {
+    let mut changed = HashSet::new();
+    for line in holo.lines() {
+        let Some(animation) = &line.animation else {
+            continue;
+        };
+        seen.insert(line.entity_id);
+        if tick % animation.interval != 0 {
+            continue;
+        }
+        let frame = line_text(holo, line);
+        if sent.get(&line.entity_id) != Some(&frame) {
+            sent.insert(line.entity_id, frame);
+            changed.insert(line.entity_id);
+        }
+    }
+    changed
+}
+
+/// Sends animated lines to their viewers whenever their frame changes. Only
+/// the lines that changed are sent, unless the client draws the whole stack
+/// as one text display.
+pub fn start_animator() {
+    tokio::spawn(async move {
+        // Last frame sent for each animated line, by entity ID
+        let mut sent = HashMap::new();
+        loop {
+            tokio::time::sleep(TICK).await;
+            let tick = TICK_COUNT.fetch_add(1, Ordering::Relaxed) + 1;
+
+            let (Some(registry), Some(tracker)) = (REGISTRY.get(), TRACKER.get()) else {
+                continue;
+            };
+            let mut seen = HashSet::new();
+
+            for npc in registry.animated_npcs().await {
+                let changed = advance(&npc, tick, &mut sent, &mut seen);
+                if changed.is_empty() {
+                    continue;
+                }
+                for player in &tracker.viewers(npc.id).await {
+                    update_lines_for_player(&npc, player, |line| changed.contains(&line.entity_id))
+                        .await;
+                }
+            }
+
+            if let Some(holograms) = HOLOGRAMS.get() {
+                for hologram in holograms.animated().await {
+                    let changed = advance(&hologram, tick, &mut sent, &mut seen);
+                    if changed.is_empty() {
+                        continue;
+                    }
+                    for player in &tracker.hologram_viewers(hologram.id).await {
+                        update_lines_for_player(&hologram, player, |line| {
+                            changed.contains(&line.entity_id)
+                        })
+                        .await;
+                    }
+                }
+            }
+
+            // Forget lines that were removed or edited
+            sent.retain(|id, _| seen.contains(id));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> LineAnimation {
+        LineAnimation::parse(text).unwrap().unwrap()
+    }
+
+    fn frames(animation: &LineAnimation, ticks: impl IntoIterator<Item = u64>) -> Vec<String> {
+        ticks
+            .into_iter()
+            .map(|tick| animation.frame_at(tick, str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn plain_lines_are_not_animated() {
+        assert!(LineAnimation::parse("Hello").unwrap().is_none());
+        assert!(LineAnimation::parse("{item:diamond}").unwrap().is_none());
+        assert!(LineAnimation::parse("{frames}").unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(LineAnimation::parse("{frames:0:a|b}").is_err());
+        assert!(LineAnimation::parse("{frames:fast:a|b}").is_err());
+        assert!(LineAnimation::parse("{typewriter:abc}").is_err());
+        assert!(LineAnimation::parse("{marquee:1:abc}").is_err());
+        assert!(LineAnimation::parse("{marquee:1:0:abc}").is_err());
+        assert_eq!(
+            LineAnimation::parse("{pulse:1:red,nope:abc}").unwrap_err(),
+            "Unknown color 'nope'"
+        );
+    }
+
+    #[test]
+    fn frames_step_every_interval() {
+        let animation = parse("{frames:2:a|b|c}");
+        assert_eq!(
+            frames(&animation, 0..8),
+            ["a", "a", "b", "b", "c", "c", "a", "a"]
+        );
+    }
+
+    #[test]
+    fn typewriter_types_then_holds() {
+        let animation = parse("{typewriter:1:abc}");
+        assert_eq!(
+            frames(&animation, 0..7),
+            ["a", "ab", "abc", "abc", "abc", "abc", "a"]
+        );
+    }
+
+    #[test]
+    fn typewriter_keeps_markup() {
+        let animation = parse("{typewriter:1:<red>ab}");
+        assert_eq!(frames(&animation, 0..2), ["<red>a", "<red>ab"]);
+    }
+
+    #[test]
+    fn marquee_scrolls_and_wraps() {
+        let animation = parse("{marquee:1:3:ab}");
+        assert_eq!(
+            frames(&animation, [0, 1, 4, 5, 6]),
+            ["ab ", "b  ", "  <reset>a", " <reset>ab", "ab "]
+        );
+    }
+
+    #[test]
+    fn pulse_fades_between_colors() {
+        let animation = parse("{pulse:1:#ff0000,#0000ff:x}");
+        assert_eq!(
+            frames(&animation, [0, 5, 10, 20]),
+            ["<#ff0000>x", "<#800080>x", "<#0000ff>x", "<#ff0000>x"]
+        );
+    }
+
+    #[test]
+    fn placeholders_are_resolved_before_cutting() {
+        let animation = parse("{typewriter:1:{online}}");
+        let frame = animation.frame_at(1, |text| text.replace("{online}", "42"));
+        assert_eq!(frame, "42");
+    }
+}
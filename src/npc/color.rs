@@ -0,0 +1,123 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// The sixteen named chat colors, as used for team colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamedColor {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+}
+
+impl NamedColor {
+    pub const ALL: [NamedColor; 16] = [
+        NamedColor::Black,
+        NamedColor::DarkBlue,
+        NamedColor::DarkGreen,
+        NamedColor::DarkAqua,
+        NamedColor::DarkRed,
+        NamedColor::DarkPurple,
+        NamedColor::Gold,
+        NamedColor::Gray,
+        NamedColor::DarkGray,
+        NamedColor::Blue,
+        NamedColor::Green,
+        NamedColor::Aqua,
+        NamedColor::Red,
+        NamedColor::LightPurple,
+        NamedColor::Yellow,
+        NamedColor::White,
+    ];
+
+    /// Team color id used by the set-player-team packet; 21 is "reset".
+    pub fn team_id(color: Option<Self>) -> i32 {
+        match color {
+            Some(color) => color as i32,
+            None => 21,
+        }
+    }
+
+    /// Color of a legacy `§` code, `0` to `f`.
+    pub fn from_legacy_code(code: char) -> Option<Self> {
+        let index = code.to_digit(16)?;
+        Some(Self::ALL[index as usize])
+    }
+
+    pub fn rgb(self) -> u32 {
+        match self {
+            NamedColor::Black => 0x000000,
+            NamedColor::DarkBlue => 0x0000AA,
+            NamedColor::DarkGreen => 0x00AA00,
+            NamedColor::DarkAqua => 0x00AAAA,
+            NamedColor::DarkRed => 0xAA0000,
+            NamedColor::DarkPurple => 0xAA00AA,
+            NamedColor::Gold => 0xFFAA00,
+            NamedColor::Gray => 0xAAAAAA,
+            NamedColor::DarkGray => 0x555555,
+            NamedColor::Blue => 0x5555FF,
+            NamedColor::Green => 0x55FF55,
+            NamedColor::Aqua => 0x55FFFF,
+            NamedColor::Red => 0xFF5555,
+            NamedColor::LightPurple => 0xFF55FF,
+            NamedColor::Yellow => 0xFFFF55,
+            NamedColor::White => 0xFFFFFF,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NamedColor::Black => "black",
+            NamedColor::DarkBlue => "dark_blue",
+            NamedColor::DarkGreen => "dark_green",
+            NamedColor::DarkAqua => "dark_aqua",
+            NamedColor::DarkRed => "dark_red",
+            NamedColor::DarkPurple => "dark_purple",
+            NamedColor::Gold => "gold",
+            NamedColor::Gray => "gray",
+            NamedColor::DarkGray => "dark_gray",
+            NamedColor::Blue => "blue",
+            NamedColor::Green => "green",
+            NamedColor::Aqua => "aqua",
+            NamedColor::Red => "red",
+            NamedColor::LightPurple => "light_purple",
+            NamedColor::Yellow => "yellow",
+            NamedColor::White => "white",
+        }
+    }
+}
+
+impl fmt::Display for NamedColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NamedColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NamedColor::ALL
+            .into_iter()
+            .find(|color| color.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<&str> = NamedColor::ALL.iter().map(|c| c.as_str()).collect();
+                format!("Unknown color '{s}' (expected {})", names.join(", "))
+            })
+    }
+}
@@ -0,0 +1,184 @@
+use pumpkin_data::entity::EntityType;
+use serde::{Deserialize, Serialize};
+
+pub const PLAYER_TYPE: &str = "minecraft:player";
+
+/// Types whose baby flag lives in the shared ageable/zombie metadata slot.
+/// Piglins keep theirs one slot further on and are left out.
+const BABY_TYPES: &[&str] = &[
+    "armadillo",
+    "axolotl",
+    "bee",
+    "camel",
+    "cat",
+    "chicken",
+    "cow",
+    "donkey",
+    "drowned",
+    "fox",
+    "frog",
+    "goat",
+    "hoglin",
+    "horse",
+    "husk",
+    "llama",
+    "mooshroom",
+    "mule",
+    "ocelot",
+    "panda",
+    "pig",
+    "polar_bear",
+    "rabbit",
+    "sheep",
+    "sniffer",
+    "strider",
+    "trader_llama",
+    "turtle",
+    "villager",
+    "wandering_trader",
+    "wolf",
+    "zoglin",
+    "zombie",
+    "zombie_villager",
+    "zombified_piglin",
+];
+
+const VILLAGER_TYPES: &[&str] = &["villager", "zombie_villager"];
+const COLORED_TYPES: &[&str] = &["sheep", "cat", "wolf"];
+
+/// Biome variants in villager type registry order.
+const VILLAGER_BIOMES: &[&str] = &[
+    "desert", "jungle", "plains", "savanna", "snow", "swamp", "taiga",
+];
+
+/// Professions in villager profession registry order.
+const PROFESSIONS: &[&str] = &[
+    "none",
+    "armorer",
+    "butcher",
+    "cartographer",
+    "cleric",
+    "farmer",
+    "fisherman",
+    "fletcher",
+    "leatherworker",
+    "librarian",
+    "mason",
+    "nitwit",
+    "shepherd",
+    "toolsmith",
+    "weaponsmith",
+];
+
+/// Dye colors in dye color id order.
+const COLORS: &[&str] = &[
+    "white",
+    "orange",
+    "magenta",
+    "light_blue",
+    "yellow",
+    "lime",
+    "pink",
+    "gray",
+    "light_gray",
+    "cyan",
+    "purple",
+    "blue",
+    "brown",
+    "green",
+    "red",
+    "black",
+];
+
+fn short_name(entity_type: &str) -> &str {
+    entity_type
+        .strip_prefix("minecraft:")
+        .unwrap_or(entity_type)
+}
+
+fn index_of(list: &[&str], value: &str, what: &str) -> Result<i32, String> {
+    list.iter()
+        .position(|v| v.eq_ignore_ascii_case(value))
+        .map(|i| i as i32)
+        .ok_or_else(|| format!("Unknown {what} '{value}' (expected {})", list.join(", ")))
+}
+
+/// Checks an entity type name and returns it namespaced, e.g. `fox` ->
+/// `minecraft:fox`.
+pub fn resolve_entity_type(name: &str) -> Result<String, String> {
+    let short = short_name(name).to_ascii_lowercase();
+    if EntityType::from_name(&short).is_none() {
+        return Err(format!("Unknown entity type '{name}'"));
+    }
+    Ok(format!("minecraft:{short}"))
+}
+
+pub fn lookup(entity_type: &str) -> &'static EntityType {
+    EntityType::from_name(short_name(entity_type)).unwrap_or(&EntityType::PLAYER)
+}
+
+pub fn supports_baby(entity_type: &str) -> bool {
+    let short = short_name(entity_type);
+    short == "armor_stand" || BABY_TYPES.contains(&short)
+}
+
+pub fn supports_villager_data(entity_type: &str) -> bool {
+    VILLAGER_TYPES.contains(&short_name(entity_type))
+}
+
+pub fn supports_color(entity_type: &str) -> bool {
+    COLORED_TYPES.contains(&short_name(entity_type))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VillagerData {
+    pub biome: String,
+    pub profession: String,
+    pub level: i32,
+}
+
+impl VillagerData {
+    pub fn new(biome: &str, profession: &str, level: i32) -> Result<Self, String> {
+        index_of(VILLAGER_BIOMES, biome, "villager biome")?;
+        index_of(PROFESSIONS, profession, "profession")?;
+        if !(1..=5).contains(&level) {
+            return Err(format!("Invalid villager level {level} (expected 1 to 5)"));
+        }
+        Ok(Self {
+            biome: biome.to_ascii_lowercase(),
+            profession: profession.to_ascii_lowercase(),
+            level,
+        })
+    }
+
+    /// Registry ids of the biome and profession, for the villager data field.
+    pub fn protocol_ids(&self) -> (i32, i32) {
+        (
+            index_of(VILLAGER_BIOMES, &self.biome, "villager biome").unwrap_or(2),
+            index_of(PROFESSIONS, &self.profession, "profession").unwrap_or(0),
+        )
+    }
+}
+
+/// Metadata that only applies to some entity types. Fields that don't apply
+/// to the NPC's type are ignored when it is spawned.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NpcEntityData {
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub baby: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub villager: Option<VillagerData>,
+    /// Wool color for sheep, collar color for cats and wolves, which are
+    /// shown as tamed so the collar is drawn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+pub fn parse_color(name: &str) -> Result<String, String> {
+    index_of(COLORS, name, "color").map(|_| name.to_ascii_lowercase())
+}
+
+pub fn color_id(name: &str) -> i32 {
+    index_of(COLORS, name, "color").unwrap_or(0)
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+use std::io::Cursor;
+use std::str::FromStr;
+
+use pumpkin_nbt::compound::NbtCompound;
+use pumpkin_nbt::deserializer::NbtReadHelper;
+use pumpkin_world::item::ItemStack;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EquipmentSlot {
+    MainHand,
+    OffHand,
+    Feet,
+    Legs,
+    Chest,
+    Head,
+}
+
+impl EquipmentSlot {
+    pub const ALL: [EquipmentSlot; 6] = [
+        EquipmentSlot::MainHand,
+        EquipmentSlot::OffHand,
+        EquipmentSlot::Feet,
+        EquipmentSlot::Legs,
+        EquipmentSlot::Chest,
+        EquipmentSlot::Head,
+    ];
+
+    /// Slot index used by the set-equipment packet.
+    pub fn protocol_id(self) -> u8 {
+        match self {
+            EquipmentSlot::MainHand => 0,
+            EquipmentSlot::OffHand => 1,
+            EquipmentSlot::Feet => 2,
+            EquipmentSlot::Legs => 3,
+            EquipmentSlot::Chest => 4,
+            EquipmentSlot::Head => 5,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EquipmentSlot::MainHand => "mainhand",
+            EquipmentSlot::OffHand => "offhand",
+            EquipmentSlot::Feet => "feet",
+            EquipmentSlot::Legs => "legs",
+            EquipmentSlot::Chest => "chest",
+            EquipmentSlot::Head => "head",
+        }
+    }
+}
+
+impl fmt::Display for EquipmentSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EquipmentSlot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EquipmentSlot::ALL
+            .into_iter()
+            .find(|slot| slot.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown slot '{s}' (expected mainhand, offhand, head, chest, legs or feet)"
+                )
+            })
+    }
+}
+
+/// An item stack as stored in the NPC file. The full stack, components
+/// included, is kept as hex encoded NBT; `id` is only there for readability.
+/// The NBT is written by `/npc equip` and not meant to be edited by hand.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NpcItem {
+    pub id: String,
+    pub nbt: String,
+    /// The stack decoded from `nbt`. `None` if it doesn't decode, in which
+    /// case the slot shows up empty.
+    #[serde(skip)]
+    stack: Option<ItemStack>,
+}
+
+impl NpcItem {
+    pub fn from_stack(stack: &ItemStack) -> Option<Self> {
+        if stack.is_empty() {
+            return None;
+        }
+        let mut compound = NbtCompound::new();
+        stack.write_item_stack(&mut compound);
+        let mut bytes = Vec::new();
+        if let Err(e) = compound.serialize_content(&mut bytes) {
+            log::error!("Failed to encode item: {e:?}");
+            return None;
+        }
+        Some(Self {
+            id: format!("minecraft:{}", stack.item.registry_key),
+            nbt: bytes.iter().map(|b| format!("{b:02x}")).collect(),
+            stack: Some(stack.clone()),
+        })
+    }
+
+    pub fn stack(&self) -> Option<&ItemStack> {
+        self.stack.as_ref()
+    }
+
+    /// Decodes `nbt` once after loading, so spawning doesn't have to.
+    pub fn init_runtime_fields(&mut self) {
+        self.stack = self.decode();
+        if self.stack.is_none() {
+            log::warn!(
+                "Failed to decode the NBT of item {}, leaving it out",
+                self.id
+            );
+        }
+    }
+
+    fn decode(&self) -> Option<ItemStack> {
+        let bytes = (0..self.nbt.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(self.nbt.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let mut reader = NbtReadHelper::new(Cursor::new(bytes));
+        let compound = NbtCompound::deserialize_content(&mut reader).ok()?;
+        ItemStack::read_item_stack(&compound)
+    }
+}
@@ -0,0 +1,950 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::BufMut;
+use pumpkin::entity::player::Player;
+use pumpkin::net::java::JavaClient;
+use pumpkin::net::ClientPlatform;
+use pumpkin_data::entity::EntityType;
+use pumpkin_data::item::Item;
+use pumpkin_data::meta_data_type::MetaDataType;
+use pumpkin_data::tracked_data::TrackedData;
+use pumpkin_data::Block;
+use pumpkin_protocol::codec::item_stack_seralizer::ItemStackSerializer;
+use pumpkin_protocol::codec::var_int::VarInt;
+use pumpkin_protocol::java::client::play::{
+    CRemoveEntities, CSetEntityMetadata, CSpawnEntity, Metadata,
+};
+use pumpkin_protocol::ser::WritingError;
+use pumpkin_util::math::vector3::Vector3;
+use pumpkin_util::text::TextComponent;
+use pumpkin_util::version::MinecraftVersion;
+use pumpkin_world::item::ItemStack;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::animator;
+use super::packets::{send_packet, send_teleport};
+use super::{HologramLine, Npc, NpcLocation};
+use crate::{text, HOLOGRAMS, REGISTRY, TRACKER};
+
+/// Text display entities were added in 1.19.4; older clients get one armor
+/// stand per line and a dropped item per item or block line.
+const TEXT_DISPLAY_PROTOCOL: u32 = 762;
+
+/// Height of a text line, which matches the line height of a text display.
+const DEFAULT_LINE_SPACING: f64 = 0.25;
+
+/// Height of an item or block line, before the style's scale.
+const OBJECT_LINE_HEIGHT: f64 = 0.6;
+
+/// Block displays draw a whole block, which is shrunk to about the size of
+/// an item.
+const BLOCK_SCALE: f32 = 0.5;
+
+/// Item display transform that draws items like an item frame does.
+const ITEM_DISPLAY_FIXED: u8 = 8;
+
+/// Ticks between two spin steps, and how far each step turns. Clients slerp
+/// the rotation but lerp the translation that keeps a block centered, so the
+/// steps are small enough for the two to stay together.
+const SPIN_INTERVAL_TICKS: u64 = 2;
+const SPIN_STEP_DEGREES: f32 = 9.0;
+
+/// Spin steps taken so far, shared by all spinning lines so they turn in sync.
+static SPIN_STEP: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Billboard {
+    Fixed,
+    Vertical,
+    Horizontal,
+    /// Always faces the viewer.
+    #[default]
+    Center,
+}
+
+impl Billboard {
+    fn protocol_id(self) -> u8 {
+        match self {
+            Billboard::Fixed => 0,
+            Billboard::Vertical => 1,
+            Billboard::Horizontal => 2,
+            Billboard::Center => 3,
+        }
+    }
+}
+
+impl FromStr for Billboard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fixed" => Ok(Billboard::Fixed),
+            "vertical" => Ok(Billboard::Vertical),
+            "horizontal" => Ok(Billboard::Horizontal),
+            "center" => Ok(Billboard::Center),
+            _ => Err(format!(
+                "Unknown billboard '{s}' (expected fixed, vertical, horizontal or center)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextAlignment {
+    #[default]
+    Center,
+    Left,
+    Right,
+}
+
+impl FromStr for TextAlignment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "center" => Ok(TextAlignment::Center),
+            "left" => Ok(TextAlignment::Left),
+            "right" => Ok(TextAlignment::Right),
+            _ => Err(format!(
+                "Unknown alignment '{s}' (expected center, left or right)"
+            )),
+        }
+    }
+}
+
+/// Background color of a text display as `#AARRGGBB`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Background(pub u32);
+
+impl Background {
+    /// Parses `#RRGGBB` with an opacity in percent.
+    pub fn parse(color: &str, opacity: u8) -> Result<Self, String> {
+        let hex = color.strip_prefix('#').unwrap_or(color);
+        let rgb = (hex.len() == 6)
+            .then(|| u32::from_str_radix(hex, 16).ok())
+            .flatten()
+            .ok_or_else(|| format!("Invalid color '{color}' (expected #RRGGBB)"))?;
+        if opacity > 100 {
+            return Err(format!("Invalid opacity {opacity} (expected 0 to 100)"));
+        }
+        let alpha = (u32::from(opacity) * 255 + 50) / 100;
+        Ok(Self(alpha << 24 | rgb))
+    }
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:08X}", self.0)
+    }
+}
+
+impl Serialize for Background {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Background {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let hex = s.strip_prefix('#').unwrap_or(&s);
+        u32::from_str_radix(hex, 16)
+            .map(Self)
+            .map_err(|_| serde::de::Error::custom(format!("invalid background color '{s}'")))
+    }
+}
+
+/// How holograms are laid out and drawn. Armor stand holograms only use the
+/// height offset and line spacing; text displays use everything else.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HologramStyle {
+    /// The client's translucent gray when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<Background>,
+    pub billboard: Billboard,
+    pub alignment: TextAlignment,
+    /// Maximum line width in pixels before text wraps.
+    pub line_width: i32,
+    pub shadow: bool,
+    pub scale: f32,
+    /// Blocks between the top of the NPC and the bottom line.
+    pub height_offset: f64,
+    /// Blocks between the bottoms of two lines.
+    pub line_spacing: f64,
+}
+
+impl Default for HologramStyle {
+    fn default() -> Self {
+        Self {
+            background: None,
+            billboard: Billboard::Center,
+            alignment: TextAlignment::Center,
+            line_width: 200,
+            shadow: false,
+            scale: 1.0,
+            height_offset: 0.25,
+            line_spacing: DEFAULT_LINE_SPACING,
+        }
+    }
+}
+
+/// Something a hologram stack floats above: an NPC or a standalone hologram.
+pub trait HologramAnchor: Sync {
+    fn location(&self) -> &NpcLocation;
+    /// Height above `location` the stack starts at for `java`, before the
+    /// style's offset.
+    fn base_height(&self, java: &JavaClient) -> f64;
+    fn lines(&self) -> &[HologramLine];
+    fn style(&self) -> &HologramStyle;
+    /// Entity ID of the text display holding all lines when they are all text.
+    fn display_entity_id(&self) -> i32;
+    fn display_uuid(&self) -> Uuid;
+    /// Server whose status fills in the line placeholders.
+    fn server(&self) -> Option<&str>;
+}
+
+impl HologramAnchor for Npc {
+    fn location(&self) -> &NpcLocation {
+        &self.location
+    }
+
+    fn base_height(&self, java: &JavaClient) -> f64 {
+        self.model_height(java)
+    }
+
+    fn lines(&self) -> &[HologramLine] {
+        &self.holograms
+    }
+
+    fn style(&self) -> &HologramStyle {
+        &self.hologram_style
+    }
+
+    fn display_entity_id(&self) -> i32 {
+        self.hologram_entity_id
+    }
+
+    fn display_uuid(&self) -> Uuid {
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_DNS,
+            format!("hologram:npc:{}", self.id).as_bytes(),
+        )
+    }
+
+    fn server(&self) -> Option<&str> {
+        self.server.as_deref()
+    }
+}
+
+fn uses_text_display(java: &JavaClient) -> bool {
+    java.version.load().protocol_version() >= TEXT_DISPLAY_PROTOCOL
+}
+
+/// A line showing an item or block instead of text.
+#[derive(Clone, Copy)]
+pub struct ObjectLine {
+    /// Shown by item lines, and by block lines on clients without display
+    /// entities. Blocks without an item, like water, have none.
+    item: Option<&'static Item>,
+    /// Set for block lines.
+    block: Option<&'static Block>,
+    pub(super) spin: bool,
+}
+
+impl fmt::Debug for ObjectLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectLine")
+            .field("item", &self.item.map(|item| item.registry_key))
+            .field("block", &self.block.map(|block| block.default_state.id))
+            .field("spin", &self.spin)
+            .finish()
+    }
+}
+
+/// Parses `{item:<id>}` or `{block:<id>}`, optionally followed by `spin`
+/// inside the braces. `Ok(None)` means the line is plain text.
+pub(super) fn parse_object(text: &str) -> Result<Option<ObjectLine>, String> {
+    let text = text.trim();
+    let Some(body) = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')) else {
+        return Ok(None);
+    };
+    let Some((kind, rest)) = body.split_once(':') else {
+        return Ok(None);
+    };
+    if kind != "item" && kind != "block" {
+        return Ok(None);
+    }
+
+    let mut words = rest.split_whitespace();
+    let id = words.next().unwrap_or_default();
+    let spin = match (words.next(), words.next()) {
+        (None, _) => false,
+        (Some(word), None) if word.eq_ignore_ascii_case("spin") => true,
+        _ => {
+            return Err(format!(
+                "Invalid line '{text}': only 'spin' may follow the {kind} ID"
+            ))
+        }
+    };
+
+    let short = id.strip_prefix("minecraft:").unwrap_or(id);
+    let item = Item::from_registry_key(short);
+    let block = match kind {
+        "block" => {
+            Some(Block::from_registry_key(short).ok_or_else(|| format!("Unknown block '{id}'"))?)
+        }
+        _ if item.is_none() => return Err(format!("Unknown item '{id}'")),
+        _ => None,
+    };
+    Ok(Some(ObjectLine { item, block, spin }))
+}
+
+/// Checks that an item or block line names something that exists and that
+/// an animated line is well formed. Plain text lines always pass.
+pub fn check_line(text: &str) -> Result<(), String> {
+    parse_object(text)?;
+    animator::check_line(text)
+}
+
+/// How a client draws a hologram stack.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// All text lines in the one text display of the anchor.
+    Stack,
+    /// A text, item or block display per line.
+    Lines,
+    /// Armor stands and dropped items for clients without display entities.
+    Legacy,
+}
+
+/// A single text display can't space its lines, so a stack with item lines
+/// or a custom line spacing is drawn a line at a time.
+fn layout(java: &JavaClient, holo: &impl HologramAnchor) -> Layout {
+    if !uses_text_display(java) {
+        Layout::Legacy
+    } else if holo.style().line_spacing != DEFAULT_LINE_SPACING
+        || holo.lines().iter().any(|line| line.object.is_some())
+    {
+        Layout::Lines
+    } else {
+        Layout::Stack
+    }
+}
+
+/// Line text with the server status placeholders filled in, at the current
+/// frame if the line is animated.
+pub(super) fn line_text(holo: &impl HologramAnchor, line: &HologramLine) -> String {
+    let resolve = |text: &str| match holo.server() {
+        Some(server_name) => crate::status::resolve_placeholders(text, server_name),
+        None => text.to_string(),
+    };
+    match &line.animation {
+        Some(animation) => animation.current_frame(resolve),
+        None => resolve(&line.text),
+    }
+}
+
+fn line_uuid(line: &HologramLine) -> Uuid {
+    Uuid::new_v5(
+        &Uuid::NAMESPACE_DNS,
+        format!("hologram:{}", line.entity_id).as_bytes(),
+    )
+}
+
+/// Bottom of the hologram stack, e.g. just above an NPC's head.
+fn base_y(java: &JavaClient, holo: &impl HologramAnchor) -> f64 {
+    holo.location().y + holo.base_height(java) + holo.style().height_offset
+}
+
+/// Height of the entity drawing each line, top line first. The stack is
+/// built up from the bottom; item and block lines are taller than text and
+/// their displays are centered in their slot. Display entities follow the
+/// style's scale, armor stands don't.
+fn line_positions(java: &JavaClient, holo: &impl HologramAnchor, layout: Layout) -> Vec<f64> {
+    let style = holo.style();
+    let scale = match layout {
+        Layout::Legacy => 1.0,
+        _ => f64::from(style.scale),
+    };
+
+    let mut y = base_y(java, holo);
+    let mut positions: Vec<f64> = holo
+        .lines()
+        .iter()
+        .rev()
+        .map(|line| {
+            let is_object = line.object.is_some();
+            let height = if is_object {
+                OBJECT_LINE_HEIGHT
+            } else {
+                style.line_spacing
+            } * scale;
+            let position = if is_object && layout != Layout::Legacy {
+                y + height / 2.0
+            } else {
+                y
+            };
+            y += height;
+            position
+        })
+        .collect();
+    positions.reverse();
+    positions
+}
+
+/// Writes the entries added by `write` as one metadata packet.
+async fn send_metadata<F>(java: &JavaClient, entity_id: i32, what: &str, write: F)
+where
+    F: FnOnce(&mut Vec<u8>, &MinecraftVersion) -> Result<(), WritingError>,
+{
+    let version = java.version.load();
+    let mut meta_buf = Vec::new();
+    if let Err(e) = write(&mut meta_buf, &version) {
+        log::error!("Failed to write {what}: {e:?}");
+        return;
+    }
+    meta_buf.put_u8(0xFF);
+    let packet = CSetEntityMetadata::new(VarInt(entity_id), meta_buf.into_boxed_slice());
+    send_packet(java, &packet).await;
+}
+
+async fn spawn_entity(
+    java: &JavaClient,
+    entity_id: i32,
+    uuid: Uuid,
+    entity_type: &EntityType,
+    position: Vector3<f64>,
+    yaw: f32,
+) -> bool {
+    let velocity = Vector3::new(0.0, 0.0, 0.0);
+    let packet = CSpawnEntity::new(
+        VarInt(entity_id),
+        uuid,
+        VarInt(i32::from(entity_type.id)),
+        position,
+        0.0,
+        yaw,
+        0.0,
+        VarInt(0),
+        velocity,
+    );
+    send_packet(java, &packet).await
+}
+
+pub async fn spawn_holograms_for_player(holo: &impl HologramAnchor, player: &Arc<Player>) {
+    if holo.lines().is_empty() {
+        return;
+    }
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+
+    let layout = layout(java, holo);
+    if layout == Layout::Stack {
+        let position = Vector3::new(holo.location().x, base_y(java, holo), holo.location().z);
+        spawn_text_display(
+            java,
+            holo,
+            holo.display_entity_id(),
+            holo.display_uuid(),
+            position,
+            stack_text(holo),
+        )
+        .await;
+        return;
+    }
+
+    for (line, y) in holo.lines().iter().zip(line_positions(java, holo, layout)) {
+        let position = Vector3::new(holo.location().x, y, holo.location().z);
+        match (line.object, layout) {
+            (None, Layout::Legacy) => spawn_armor_stand_line(java, holo, line, position).await,
+            (None, _) => {
+                let text = text::parse(&line_text(holo, line));
+                spawn_text_display(java, holo, line.entity_id, line_uuid(line), position, text)
+                    .await;
+            }
+            (Some(object), Layout::Legacy) => {
+                spawn_dropped_item(java, line, object, position).await;
+            }
+            (Some(object), _) => spawn_object_display(java, holo, line, object, position).await,
+        }
+    }
+}
+
+pub async fn despawn_holograms_for_player(holo: &impl HologramAnchor, player: &Arc<Player>) {
+    if holo.lines().is_empty() {
+        return;
+    }
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+    send_packet(java, &CRemoveEntities::new(&entity_ids(holo))).await;
+}
+
+/// Every entity a hologram stack may be drawn with.
+pub(super) fn entity_ids(holo: &impl HologramAnchor) -> Vec<VarInt> {
+    let mut ids = vec![VarInt(holo.display_entity_id())];
+    ids.extend(holo.lines().iter().map(|h| VarInt(h.entity_id)));
+    ids
+}
+
+/// Moves the hologram stack to follow its anchor's location and height.
+pub(super) async fn move_holograms(java: &JavaClient, holo: &impl HologramAnchor) {
+    if holo.lines().is_empty() {
+        return;
+    }
+
+    let yaw = holo.location().yaw;
+    let layout = layout(java, holo);
+    if layout == Layout::Stack {
+        let position = Vector3::new(holo.location().x, base_y(java, holo), holo.location().z);
+        send_teleport(java, holo.display_entity_id(), position, yaw, 0.0, false).await;
+        return;
+    }
+
+    for (line, y) in holo.lines().iter().zip(line_positions(java, holo, layout)) {
+        let position = Vector3::new(holo.location().x, y, holo.location().z);
+        let yaw = if layout == Layout::Legacy { 0.0 } else { yaw };
+        send_teleport(java, line.entity_id, position, yaw, 0.0, false).await;
+    }
+}
+
+/// Resends the hologram text, e.g. after a placeholder value changed.
+pub async fn update_text_for_player(holo: &impl HologramAnchor, player: &Arc<Player>) {
+    update_lines_for_player(holo, player, |_| true).await;
+}
+
+/// Resends the text of the lines `changed` picks. A stack drawn as a single
+/// text display is resent whole if any of its lines changed.
+pub(super) async fn update_lines_for_player(
+    holo: &impl HologramAnchor,
+    player: &Arc<Player>,
+    changed: impl Fn(&HologramLine) -> bool,
+) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+
+    let layout = layout(java, holo);
+    if layout == Layout::Stack {
+        if !holo.lines().iter().any(&changed) {
+            return;
+        }
+        send_metadata(
+            java,
+            holo.display_entity_id(),
+            "hologram text update",
+            |buf, version| {
+                Metadata::new(
+                    TrackedData::DATA_TEXT,
+                    MetaDataType::TextComponent,
+                    stack_text(holo),
+                )
+                .write(buf, version)
+            },
+        )
+        .await;
+        return;
+    }
+
+    for line in holo.lines() {
+        if !changed(line) || line.object.is_some() {
+            continue;
+        }
+        let text = text::parse(&line_text(holo, line));
+        send_metadata(
+            java,
+            line.entity_id,
+            "hologram text update",
+            |buf, version| match layout {
+                Layout::Legacy => Metadata::new(
+                    TrackedData::DATA_CUSTOM_NAME,
+                    MetaDataType::OptionalTextComponent,
+                    Some(text),
+                )
+                .write(buf, version),
+                _ => Metadata::new(TrackedData::DATA_TEXT, MetaDataType::TextComponent, text)
+                    .write(buf, version),
+            },
+        )
+        .await;
+    }
+}
+
+/// All lines in one component. Each line is parsed on its own so formatting
+/// doesn't carry over to the next one.
+fn stack_text(holo: &impl HologramAnchor) -> TextComponent {
+    let mut stack = TextComponent::text(String::new());
+    for (i, line) in holo.lines().iter().enumerate() {
+        if i > 0 {
+            stack = stack.add_child(TextComponent::text("\n"));
+        }
+        stack = stack.add_child(text::parse(&line_text(holo, line)));
+    }
+    stack
+}
+
+#[derive(Clone, Copy, Serialize)]
+struct Vector3f(f32, f32, f32);
+
+#[derive(Clone, Copy, Serialize)]
+struct Quaternionf(f32, f32, f32, f32);
+
+/// Rotates `v` by the unit quaternion `q`.
+fn rotate(q: Quaternionf, v: Vector3f) -> Vector3f {
+    let Quaternionf(qx, qy, qz, w) = q;
+    let Vector3f(x, y, z) = v;
+    // v + 2w(u × v) + 2u × (u × v), with u the vector part of q
+    let (cx, cy, cz) = (qy * z - qz * y, qz * x - qx * z, qx * y - qy * x);
+    let (dx, dy, dz) = (qy * cz - qz * cy, qz * cx - qx * cz, qx * cy - qy * cx);
+    Vector3f(
+        x + 2.0 * (w * cx + dx),
+        y + 2.0 * (w * cy + dy),
+        z + 2.0 * (w * cz + dz),
+    )
+}
+
+async fn spawn_text_display(
+    java: &JavaClient,
+    holo: &impl HologramAnchor,
+    entity_id: i32,
+    uuid: Uuid,
+    position: Vector3<f64>,
+    text: TextComponent,
+) {
+    let yaw = holo.location().yaw;
+    if !spawn_entity(
+        java,
+        entity_id,
+        uuid,
+        &EntityType::TEXT_DISPLAY,
+        position,
+        yaw,
+    )
+    .await
+    {
+        return;
+    }
+
+    let style = holo.style();
+    send_metadata(java, entity_id, "hologram metadata", |buf, version| {
+        Metadata::new(
+            TrackedData::DATA_BILLBOARD,
+            MetaDataType::Byte,
+            style.billboard.protocol_id(),
+        )
+        .write(buf, version)?;
+        Metadata::new(
+            TrackedData::DATA_SCALE,
+            MetaDataType::Vector3f,
+            Vector3f(style.scale, style.scale, style.scale),
+        )
+        .write(buf, version)?;
+        Metadata::new(TrackedData::DATA_TEXT, MetaDataType::TextComponent, text)
+            .write(buf, version)?;
+        Metadata::new(
+            TrackedData::DATA_LINE_WIDTH,
+            MetaDataType::Integer,
+            VarInt(style.line_width),
+        )
+        .write(buf, version)?;
+        if let Some(background) = style.background {
+            Metadata::new(
+                TrackedData::DATA_BACKGROUND,
+                MetaDataType::Integer,
+                VarInt(background.0 as i32),
+            )
+            .write(buf, version)?;
+        }
+
+        let mut flags = 0u8;
+        if style.shadow {
+            flags |= 0x01;
+        }
+        flags |= match style.alignment {
+            TextAlignment::Center => 0,
+            TextAlignment::Left => 0x08,
+            TextAlignment::Right => 0x10,
+        };
+        Metadata::new(
+            TrackedData::DATA_TEXT_DISPLAY_FLAGS,
+            MetaDataType::Byte,
+            flags,
+        )
+        .write(buf, version)
+    })
+    .await;
+}
+
+async fn spawn_object_display(
+    java: &JavaClient,
+    holo: &impl HologramAnchor,
+    line: &HologramLine,
+    object: ObjectLine,
+    position: Vector3<f64>,
+) {
+    let entity_type = match object.block {
+        Some(_) => &EntityType::BLOCK_DISPLAY,
+        None => &EntityType::ITEM_DISPLAY,
+    };
+    let yaw = holo.location().yaw;
+    if !spawn_entity(
+        java,
+        line.entity_id,
+        line_uuid(line),
+        entity_type,
+        position,
+        yaw,
+    )
+    .await
+    {
+        return;
+    }
+
+    let style = holo.style();
+    let step = if object.spin {
+        SPIN_STEP.load(Ordering::Relaxed)
+    } else {
+        0
+    };
+    send_metadata(
+        java,
+        line.entity_id,
+        "hologram item metadata",
+        |buf, version| {
+            match (object.block, object.item) {
+                (Some(block), _) => {
+                    let scale = BLOCK_SCALE * style.scale;
+                    Metadata::new(
+                        TrackedData::DATA_SCALE,
+                        MetaDataType::Vector3f,
+                        Vector3f(scale, scale, scale),
+                    )
+                    .write(buf, version)?;
+                    Metadata::new(
+                        TrackedData::DATA_BLOCK_STATE,
+                        MetaDataType::BlockState,
+                        VarInt(i32::from(block.default_state.id)),
+                    )
+                    .write(buf, version)?;
+                }
+                (None, Some(item)) => {
+                    Metadata::new(
+                        TrackedData::DATA_SCALE,
+                        MetaDataType::Vector3f,
+                        Vector3f(style.scale, style.scale, style.scale),
+                    )
+                    .write(buf, version)?;
+                    Metadata::new(
+                        TrackedData::DATA_ITEM,
+                        MetaDataType::ItemStack,
+                        ItemStackSerializer::from(ItemStack::new(1, item)),
+                    )
+                    .write(buf, version)?;
+                    Metadata::new(
+                        TrackedData::DATA_ITEM_DISPLAY,
+                        MetaDataType::Byte,
+                        ITEM_DISPLAY_FIXED,
+                    )
+                    .write(buf, version)?;
+                }
+                (None, None) => {}
+            }
+            write_turn(buf, version, object, style, step)
+        },
+    )
+    .await;
+}
+
+/// Clients without display entities get a dropped item, which bobs and
+/// spins on its own.
+async fn spawn_dropped_item(
+    java: &JavaClient,
+    line: &HologramLine,
+    object: ObjectLine,
+    position: Vector3<f64>,
+) {
+    let Some(item) = object.item else {
+        return;
+    };
+    if !spawn_entity(
+        java,
+        line.entity_id,
+        line_uuid(line),
+        &EntityType::ITEM,
+        position,
+        0.0,
+    )
+    .await
+    {
+        return;
+    }
+
+    send_metadata(
+        java,
+        line.entity_id,
+        "hologram item metadata",
+        |buf, version| {
+            Metadata::new(TrackedData::DATA_NO_GRAVITY, MetaDataType::Boolean, true)
+                .write(buf, version)?;
+            Metadata::new(
+                TrackedData::DATA_STACK,
+                MetaDataType::ItemStack,
+                ItemStackSerializer::from(ItemStack::new(1, item)),
+            )
+            .write(buf, version)
+        },
+    )
+    .await;
+}
+
+async fn spawn_armor_stand_line(
+    java: &JavaClient,
+    holo: &impl HologramAnchor,
+    line: &HologramLine,
+    position: Vector3<f64>,
+) {
+    let uuid = line_uuid(line);
+    if !spawn_entity(
+        java,
+        line.entity_id,
+        uuid,
+        &EntityType::ARMOR_STAND,
+        position,
+        0.0,
+    )
+    .await
+    {
+        return;
+    }
+
+    let name = text::parse(&line_text(holo, line));
+    send_metadata(java, line.entity_id, "hologram metadata", |buf, version| {
+        Metadata::new(TrackedData::DATA_FLAGS, MetaDataType::Byte, 0x20u8).write(buf, version)?;
+        Metadata::new(
+            TrackedData::DATA_CUSTOM_NAME,
+            MetaDataType::OptionalTextComponent,
+            Some(name),
+        )
+        .write(buf, version)?;
+        Metadata::new(TrackedData::DATA_NAME_VISIBLE, MetaDataType::Boolean, true)
+            .write(buf, version)?;
+        Metadata::new(TrackedData::DATA_NO_GRAVITY, MetaDataType::Boolean, true)
+            .write(buf, version)?;
+        Metadata::new(
+            TrackedData::DATA_ARMOR_STAND_FLAGS,
+            MetaDataType::Byte,
+            16u8,
+        )
+        .write(buf, version)
+    })
+    .await;
+}
+
+/// Rotation of spinning lines after `step` spin steps.
+fn spin_angle(step: u32) -> f32 {
+    let steps_per_turn = (360.0 / SPIN_STEP_DEGREES) as u32;
+    (step % steps_per_turn) as f32 * SPIN_STEP_DEGREES.to_radians()
+}
+
+/// Writes the rotation of an item or block display turned to `step`. Block
+/// displays draw from their corner, so their translation is the block's
+/// center turned by the same rotation, which keeps it on the entity.
+fn write_turn(
+    buf: &mut Vec<u8>,
+    version: &MinecraftVersion,
+    object: ObjectLine,
+    style: &HologramStyle,
+    step: u32,
+) -> Result<(), WritingError> {
+    let (sin, cos) = (spin_angle(step) / 2.0).sin_cos();
+    let rotation = Quaternionf(0.0, sin, 0.0, cos);
+    Metadata::new(
+        TrackedData::DATA_LEFT_ROTATION,
+        MetaDataType::Quaternionf,
+        rotation,
+    )
+    .write(buf, version)?;
+
+    if object.block.is_some() {
+        let half = BLOCK_SCALE * style.scale / 2.0;
+        let Vector3f(x, y, z) = rotate(rotation, Vector3f(half, half, half));
+        Metadata::new(
+            TrackedData::DATA_TRANSLATION,
+            MetaDataType::Vector3f,
+            Vector3f(-x, -y, -z),
+        )
+        .write(buf, version)?;
+    }
+    Ok(())
+}
+
+/// Turns the spinning lines one step further, interpolated over the time
+/// until the next step.
+async fn spin_for_player(holo: &impl HologramAnchor, player: &Arc<Player>, step: u32) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+    if !uses_text_display(java) {
+        return;
+    }
+
+    for line in holo.lines() {
+        let Some(object) = line.object.filter(|object| object.spin) else {
+            continue;
+        };
+        send_metadata(java, line.entity_id, "hologram spin", |buf, version| {
+            Metadata::new(
+                TrackedData::DATA_START_INTERPOLATION,
+                MetaDataType::Integer,
+                VarInt(0),
+            )
+            .write(buf, version)?;
+            Metadata::new(
+                TrackedData::DATA_INTERPOLATION_DURATION,
+                MetaDataType::Integer,
+                VarInt(SPIN_INTERVAL_TICKS as i32),
+            )
+            .write(buf, version)?;
+            write_turn(buf, version, object, holo.style(), step)
+        })
+        .await;
+    }
+}
+
+/// Spins item and block lines marked `spin` for the players viewing them.
+/// Clients interpolate between steps, so the lines turn smoothly.
+pub fn start_spin_task() {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(SPIN_INTERVAL_TICKS * 50)).await;
+            let step = SPIN_STEP.fetch_add(1, Ordering::Relaxed) + 1;
+
+            let (Some(registry), Some(tracker)) = (REGISTRY.get(), TRACKER.get()) else {
+                continue;
+            };
+            for npc in &registry.spinning_npcs().await {
+                for player in &tracker.viewers(npc.id).await {
+                    spin_for_player(npc, player, step).await;
+                }
+            }
+
+            let Some(holograms) = HOLOGRAMS.get() else {
+                continue;
+            };
+            for hologram in &holograms.spinning().await {
+                for player in &tracker.hologram_viewers(hologram.id).await {
+                    spin_for_player(hologram, player, step).await;
+                }
+            }
+        }
+    });
+}
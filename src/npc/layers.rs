@@ -0,0 +1,146 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Which overlay layers of the skin are shown. All layers are on by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkinLayers {
+    pub cape: bool,
+    pub jacket: bool,
+    pub left_sleeve: bool,
+    pub right_sleeve: bool,
+    pub left_pants: bool,
+    pub right_pants: bool,
+    pub hat: bool,
+}
+
+impl Default for SkinLayers {
+    fn default() -> Self {
+        Self {
+            cape: true,
+            jacket: true,
+            left_sleeve: true,
+            right_sleeve: true,
+            left_pants: true,
+            right_pants: true,
+            hat: true,
+        }
+    }
+}
+
+impl SkinLayers {
+    pub const NAMES: [&'static str; 9] = [
+        "cape",
+        "jacket",
+        "sleeves",
+        "left_sleeve",
+        "right_sleeve",
+        "pants",
+        "left_pants",
+        "right_pants",
+        "hat",
+    ];
+
+    /// Value of the player model customization metadata byte.
+    pub fn mask(self) -> u8 {
+        [
+            self.cape,
+            self.jacket,
+            self.left_sleeve,
+            self.right_sleeve,
+            self.left_pants,
+            self.right_pants,
+            self.hat,
+        ]
+        .into_iter()
+        .enumerate()
+        .fold(
+            0,
+            |mask, (bit, on)| if on { mask | (1 << bit) } else { mask },
+        )
+    }
+
+    /// Flips the named layer and returns whether it is now shown. `sleeves`
+    /// and `pants` switch both sides together.
+    pub fn toggle(&mut self, name: &str) -> Result<bool, String> {
+        let layers: Vec<&mut bool> = match name.to_ascii_lowercase().as_str() {
+            "cape" => vec![&mut self.cape],
+            "jacket" => vec![&mut self.jacket],
+            "sleeves" => vec![&mut self.left_sleeve, &mut self.right_sleeve],
+            "left_sleeve" => vec![&mut self.left_sleeve],
+            "right_sleeve" => vec![&mut self.right_sleeve],
+            "pants" => vec![&mut self.left_pants, &mut self.right_pants],
+            "left_pants" => vec![&mut self.left_pants],
+            "right_pants" => vec![&mut self.right_pants],
+            "hat" => vec![&mut self.hat],
+            _ => {
+                return Err(format!(
+                    "Unknown layer '{name}' (expected {})",
+                    Self::NAMES.join(", ")
+                ))
+            }
+        };
+        let on = !layers.iter().all(|l| **l);
+        for layer in layers {
+            *layer = on;
+        }
+        Ok(on)
+    }
+
+    /// Names of the layers that are shown, for display.
+    pub fn shown(self) -> Vec<&'static str> {
+        [
+            ("cape", self.cape),
+            ("jacket", self.jacket),
+            ("left_sleeve", self.left_sleeve),
+            ("right_sleeve", self.right_sleeve),
+            ("left_pants", self.left_pants),
+            ("right_pants", self.right_pants),
+            ("hat", self.hat),
+        ]
+        .into_iter()
+        .filter_map(|(name, on)| on.then_some(name))
+        .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MainHand {
+    Left,
+    #[default]
+    Right,
+}
+
+impl MainHand {
+    /// Value of the main arm metadata byte.
+    pub fn protocol_id(self) -> u8 {
+        match self {
+            MainHand::Left => 0,
+            MainHand::Right => 1,
+        }
+    }
+}
+
+impl fmt::Display for MainHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MainHand::Left => "left",
+            MainHand::Right => "right",
+        })
+    }
+}
+
+impl FromStr for MainHand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "left" => Ok(MainHand::Left),
+            "right" => Ok(MainHand::Right),
+            _ => Err(format!("Unknown hand '{s}' (expected left or right)")),
+        }
+    }
+}
@@ -1,11 +1,38 @@
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
 
+use pumpkin::entity::player::Player;
+use pumpkin::net::java::JavaClient;
+use pumpkin::server::Server;
+use pumpkin::world::World;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use animation::AutoAnimations;
+use animator::LineAnimation;
+use color::NamedColor;
+use entity::NpcEntityData;
+use equipment::{EquipmentSlot, NpcItem};
+use hologram::{HologramStyle, ObjectLine};
+use layers::{MainHand, SkinLayers};
+use pose::NpcPose;
+use tab::NpcTab;
+
+pub mod animation;
+pub mod animator;
+pub mod color;
+pub mod entity;
+pub mod equipment;
+pub mod hologram;
+pub mod layers;
 pub mod packets;
+pub mod pose;
 pub mod registry;
 pub mod skin;
+pub mod storage;
+pub mod tab;
+pub mod tracker;
 
 /// Entity IDs count down from -1000 to avoid collision with PumpkinMC's
 /// CURRENT_ID which starts at 0 and increments.
@@ -20,8 +47,65 @@ fn npc_uuid(id: u32) -> Uuid {
     Uuid::new_v5(&Uuid::NAMESPACE_DNS, format!("npc:{id}").as_bytes())
 }
 
+/// Dimension NPCs created before worlds were tracked are assumed to live in.
+pub const DEFAULT_WORLD: &str = "minecraft:overworld";
+
+/// Namespaced dimension key of a world, e.g. `minecraft:the_nether`.
+pub fn world_key(world: &World) -> String {
+    let name = world.dimension.minecraft_name;
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{name}")
+    }
+}
+
+pub fn player_world(player: &Player) -> String {
+    world_key(&player.world())
+}
+
+/// Loaded world with the given dimension key.
+pub fn find_world(server: &Server, key: &str) -> Option<Arc<World>> {
+    server
+        .worlds
+        .load()
+        .iter()
+        .find(|w| world_key(w) == key)
+        .cloned()
+}
+
+/// Keys are lowercase words made of `a-z`, `0-9`, `_` and `-`. Purely numeric
+/// keys are rejected so they can't be confused with IDs.
+pub fn is_valid_key(key: &str) -> bool {
+    (1..=32).contains(&key.len())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        && !key.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Turns a display name into a key candidate, e.g. `Lobby Portal` -> `lobby_portal`.
+pub fn key_from_name(name: &str) -> String {
+    let mut key = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            key.push(c.to_ascii_lowercase());
+        } else if !key.is_empty() && !key.ends_with('_') {
+            key.push('_');
+        }
+    }
+    let key = key.trim_end_matches('_');
+    let key: String = key.chars().take(28).collect();
+    if is_valid_key(&key) {
+        key
+    } else {
+        "npc".to_string()
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NpcLocation {
+    pub world: String,
     pub x: f64,
     pub y: f64,
     pub z: f64,
@@ -40,43 +124,262 @@ pub struct HologramLine {
     pub text: String,
     #[serde(skip)]
     pub entity_id: i32,
+    /// Parsed animation of an animated line. Lines that fail to parse, e.g.
+    /// after a hand edit, count as plain text.
+    #[serde(skip)]
+    pub animation: Option<Arc<LineAnimation>>,
+    /// Parsed item or block of an item or block line. Lines that fail to
+    /// parse count as plain text too.
+    #[serde(skip)]
+    pub object: Option<ObjectLine>,
+}
+
+impl HologramLine {
+    pub fn new(text: String) -> Self {
+        let mut line = Self {
+            text,
+            entity_id: 0,
+            animation: None,
+            object: None,
+        };
+        line.init_runtime_fields();
+        line
+    }
+
+    pub fn init_runtime_fields(&mut self) {
+        self.entity_id = next_entity_id();
+        self.animation = LineAnimation::parse(&self.text)
+            .ok()
+            .flatten()
+            .map(Arc::new);
+        self.object = hologram::parse_object(&self.text).ok().flatten();
+    }
+
+    /// Whether the line is an item or block marked `spin`.
+    pub fn spins(&self) -> bool {
+        self.object.is_some_and(|object| object.spin)
+    }
+}
+
+/// Real nametag shown above the NPC, rendered by the client from the team
+/// prefix, the NPC's name and the team suffix.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NpcNametag {
+    pub visible: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub prefix: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub suffix: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Npc {
     pub id: u32,
-    pub name: String,
+    /// Unique, human readable handle used to refer to the NPC in commands.
+    pub key: String,
+    /// Name shown to players, e.g. in the nametag. The profile name the
+    /// client sees is `profile_name()`.
+    pub display_name: String,
     #[serde(skip)]
     pub uuid: Uuid,
     #[serde(skip)]
     pub entity_id: i32,
+    /// Invisible armor stand the NPC rides while sitting.
+    #[serde(skip)]
+    pub seat_entity_id: i32,
+    /// Namespaced entity type, `minecraft:player` unless set otherwise.
+    #[serde(
+        default = "default_entity_type",
+        skip_serializing_if = "is_player_type"
+    )]
+    pub entity_type: String,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub entity_data: NpcEntityData,
     pub location: NpcLocation,
     pub skin: Option<NpcSkin>,
     pub look_at_nearest: bool,
     pub holograms: Vec<HologramLine>,
+    /// Text display holding all hologram lines for clients that support it.
+    #[serde(skip)]
+    pub hologram_entity_id: i32,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub hologram_style: HologramStyle,
     pub server: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub equipment: BTreeMap<EquipmentSlot, NpcItem>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub pose: NpcPose,
+    /// Size multiplier, sent as the scale attribute.
+    #[serde(default = "default_scale", skip_serializing_if = "is_default_scale")]
+    pub scale: f64,
+    /// Glowing outline shown to everyone, colored by `glow_color`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub glowing: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glow_color: Option<NamedColor>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub nametag: NpcNametag,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub tab: NpcTab,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub animations: AutoAnimations,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub skin_layers: SkinLayers,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub main_hand: MainHand,
+}
+
+/// Bounds of the scale attribute.
+pub const SCALE_RANGE: std::ops::RangeInclusive<f64> = 0.0625..=16.0;
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+fn is_default_scale(scale: &f64) -> bool {
+    *scale == 1.0
+}
+
+fn default_entity_type() -> String {
+    entity::PLAYER_TYPE.to_string()
+}
+
+fn is_player_type(entity_type: &str) -> bool {
+    entity_type == entity::PLAYER_TYPE
+}
+
+pub(crate) fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
 }
 
 impl Npc {
-    pub fn new(id: u32, name: String, location: NpcLocation, skin: Option<NpcSkin>) -> Self {
+    pub fn new(
+        id: u32,
+        key: String,
+        display_name: String,
+        location: NpcLocation,
+        skin: Option<NpcSkin>,
+    ) -> Self {
         Self {
             uuid: npc_uuid(id),
             entity_id: next_entity_id(),
+            seat_entity_id: next_entity_id(),
             id,
-            name,
+            key,
+            display_name,
+            entity_type: default_entity_type(),
+            entity_data: NpcEntityData::default(),
             location,
             skin,
             look_at_nearest: false,
             holograms: Vec::new(),
+            hologram_entity_id: next_entity_id(),
+            hologram_style: HologramStyle::default(),
             server: None,
+            equipment: BTreeMap::new(),
+            pose: NpcPose::Standing,
+            scale: 1.0,
+            glowing: false,
+            glow_color: None,
+            nametag: NpcNametag::default(),
+            tab: NpcTab::default(),
+            animations: AutoAnimations::default(),
+            skin_layers: SkinLayers::default(),
+            main_hand: MainHand::Right,
         }
     }
 
+    /// Player NPCs get a player info entry, a skin and a team; other types
+    /// are plain entities.
+    pub fn is_player(&self) -> bool {
+        is_player_type(&self.entity_type)
+    }
+
+    /// Game profile name sent to clients: the ID as hex digits, each behind a
+    /// `§`, so it renders as nothing and can never clash with a real player
+    /// or another NPC. Exactly 16 characters, the protocol limit.
+    pub fn profile_name(&self) -> String {
+        format!("{:08x}", self.id)
+            .chars()
+            .flat_map(|c| ['§', c])
+            .collect()
+    }
+
+    /// Size `java` draws the NPC at, which is the normal size for clients
+    /// without the scale attribute.
+    pub fn scale_seen_by(&self, java: &JavaClient) -> f64 {
+        if packets::supports_scale(java) {
+            self.scale
+        } else {
+            1.0
+        }
+    }
+
+    /// Height of the top of the model above the NPC's location, as `java`
+    /// draws it.
+    pub fn model_height(&self, java: &JavaClient) -> f64 {
+        let height = if self.is_player() {
+            self.pose.height()
+        } else {
+            let height = f64::from(entity::lookup(&self.entity_type).dimension[1]);
+            if self.entity_data.baby {
+                height * 0.5
+            } else {
+                height
+            }
+        };
+        height * self.scale_seen_by(java)
+    }
+
     pub fn init_runtime_fields(&mut self) {
         self.uuid = npc_uuid(self.id);
         self.entity_id = next_entity_id();
+        self.seat_entity_id = next_entity_id();
+        self.hologram_entity_id = next_entity_id();
         for line in &mut self.holograms {
-            line.entity_id = next_entity_id();
+            line.init_runtime_fields();
         }
+        for item in self.equipment.values_mut() {
+            item.init_runtime_fields();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_keys() {
+        assert!(is_valid_key("lobby_portal"));
+        assert!(is_valid_key("npc-2"));
+        assert!(is_valid_key("7up"));
+        assert!(is_valid_key(&"a".repeat(32)));
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("123"));
+        assert!(!is_valid_key("Lobby"));
+        assert!(!is_valid_key("lobby portal"));
+        assert!(!is_valid_key("café"));
+        assert!(!is_valid_key(&"a".repeat(33)));
+    }
+
+    #[test]
+    fn derives_keys_from_names() {
+        assert_eq!(key_from_name("Lobby Portal"), "lobby_portal");
+        assert_eq!(key_from_name("  Hello, World!  "), "hello_world");
+        assert_eq!(key_from_name("Shop 2"), "shop_2");
+        assert_eq!(key_from_name(&"a".repeat(40)), "a".repeat(28));
+    }
+
+    #[test]
+    fn falls_back_when_a_name_gives_no_key() {
+        assert_eq!(key_from_name("!!!"), "npc");
+        assert_eq!(key_from_name("2024"), "npc");
+        assert_eq!(key_from_name(""), "npc");
     }
 }
@@ -2,24 +2,34 @@ use std::sync::Arc;
 
 use bytes::BufMut;
 use pumpkin::entity::player::Player;
+use pumpkin::net::java::JavaClient;
 use pumpkin::net::ClientPlatform;
+use pumpkin_data::attributes::Attributes;
 use pumpkin_data::entity::EntityType;
 use pumpkin_data::meta_data_type::MetaDataType;
 use pumpkin_data::tracked_data::TrackedData;
 use pumpkin_protocol::codec::var_int::VarInt;
 use pumpkin_protocol::java::client::play::{
-    CHeadRot, CPlayerInfoUpdate, CRemoveEntities, CRemovePlayerInfo, CSetEntityMetadata,
-    CSpawnEntity, CUpdateEntityRot, Metadata, Player as ProtocolPlayer, PlayerAction,
-    PlayerInfoFlags,
+    CEntityPositionSync, CHeadRot, CPlayerInfoUpdate, CRemoveEntities, CRemovePlayerInfo,
+    CSetEntityMetadata, CSetPassengers, CSpawnEntity, CUpdateEntityRot, Metadata,
+    Player as ProtocolPlayer, PlayerAction, PlayerInfoFlags,
 };
 use pumpkin_util::math::vector3::Vector3;
 
 use pumpkin_util::text::TextComponent;
+use pumpkin_world::item::ItemStack;
 use uuid::Uuid;
 
 use pumpkin_protocol::ser::NetworkWriteExt;
 
-use super::{HologramLine, Npc};
+use super::animation::NpcAnimation;
+use super::color::NamedColor;
+use super::entity;
+use super::equipment::{EquipmentSlot, NpcItem};
+use super::hologram;
+use super::pose::NpcPose;
+use super::Npc;
+use crate::text;
 
 fn write_text_component(
     buf: &mut Vec<u8>,
@@ -32,27 +42,136 @@ fn write_text_component(
         .map_err(|e| pumpkin_protocol::ser::WritingError::Serde(e.to_string()))
 }
 
-async fn send_team_nametag_hide(java: &pumpkin::net::java::JavaClient, npc: &Npc) -> bool {
+fn write_item_stack(
+    buf: &mut Vec<u8>,
+    stack: ItemStack,
+) -> Result<(), pumpkin_protocol::ser::WritingError> {
+    use pumpkin_protocol::codec::item_stack_seralizer::ItemStackSerializer;
+    use pumpkin_protocol::ser::serializer::Serializer as ProtocolSerializer;
+    use serde::Serialize;
+    let mut ser = ProtocolSerializer::new(buf);
+    ItemStackSerializer::from(stack)
+        .serialize(&mut ser)
+        .map_err(|e| pumpkin_protocol::ser::WritingError::Serde(e.to_string()))
+}
+
+/// Sends the NPC's items in `slots`; slots without an item are cleared.
+pub async fn send_equipment_for_player(npc: &Npc, player: &Arc<Player>, slots: &[EquipmentSlot]) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+    send_equipment(java, npc, slots).await;
+}
+
+async fn send_equipment(
+    java: &pumpkin::net::java::JavaClient,
+    npc: &Npc,
+    slots: &[EquipmentSlot],
+) -> bool {
+    if slots.is_empty() {
+        return true;
+    }
+
+    let version = java.version.load();
+    let packet_id = pumpkin_data::packet::clientbound::PLAY_SET_EQUIPMENT.to_id(version);
+
+    let mut buf: Vec<u8> = Vec::new();
+    let result: Result<(), pumpkin_protocol::ser::WritingError> = (|| {
+        buf.write_var_int(&VarInt(packet_id))?;
+        buf.write_var_int(&VarInt(npc.entity_id))?;
+        for (i, slot) in slots.iter().enumerate() {
+            // The top bit marks that another entry follows
+            let more = if i + 1 < slots.len() { 0x80 } else { 0 };
+            buf.write_u8(slot.protocol_id() | more)?;
+            let stack = npc
+                .equipment
+                .get(slot)
+                .and_then(NpcItem::stack)
+                .unwrap_or(&ItemStack::EMPTY)
+                .clone();
+            write_item_stack(&mut buf, stack)?;
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => {
+            java.enqueue_packet_data(buf.into()).await;
+            true
+        }
+        Err(e) => {
+            log::error!("Failed to write equipment packet: {e:?}");
+            false
+        }
+    }
+}
+
+/// Team entry of an NPC: the profile name for players, the UUID for other
+/// entities.
+fn team_member(npc: &Npc) -> String {
+    if npc.is_player() {
+        npc.profile_name()
+    } else {
+        npc.uuid.to_string()
+    }
+}
+
+/// The tab list sorts entries without a list order by team name, so the tab
+/// group leads the name.
+fn team_name(npc: &Npc) -> String {
+    match &npc.tab.group {
+        Some(group) => format!("npc_{group}_{}", npc.entity_id),
+        None => format!("npc_{}", npc.entity_id),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TeamMode {
+    Create,
+    Remove,
+    Update,
+}
+
+/// Sends the per-NPC team that hides the nametag and sets the glow color.
+async fn send_team(java: &pumpkin::net::java::JavaClient, npc: &Npc, mode: TeamMode) -> bool {
     let version = java.version.load();
     let packet_id = pumpkin_data::packet::clientbound::PLAY_SET_PLAYER_TEAM.to_id(version);
 
     let mut buf: Vec<u8> = Vec::new();
     let empty_tc = TextComponent::text(String::new());
+    let tag = &npc.nametag;
 
     let result: Result<(), pumpkin_protocol::ser::WritingError> = (|| {
         buf.write_var_int(&VarInt(packet_id))?;
-        let team_name = format!("npc_{}", npc.entity_id);
-        buf.write_string(&team_name)?;
-        buf.write_i8(0)?; // mode: create
+        buf.write_string(&team_name(npc))?;
+        buf.write_i8(match mode {
+            TeamMode::Create => 0,
+            TeamMode::Remove => 1,
+            TeamMode::Update => 2,
+        })?;
+        if mode == TeamMode::Remove {
+            return Ok(());
+        }
+
         write_text_component(&mut buf, &empty_tc)?; // display name
         buf.write_i8(0)?; // friendly flags
-        buf.write_var_int(&VarInt(1))?; // nametag visibility: never
+                          // nametag visibility: always or never
+        buf.write_var_int(&VarInt(if tag.visible { 0 } else { 1 }))?;
         buf.write_var_int(&VarInt(1))?; // collision rule: never
-        buf.write_var_int(&VarInt(21))?; // team color: reset
-        write_text_component(&mut buf, &empty_tc)?; // prefix
-        write_text_component(&mut buf, &empty_tc)?; // suffix
-        buf.write_var_int(&VarInt(1))?;
-        buf.write_string(&npc.name)?;
+        buf.write_var_int(&VarInt(NamedColor::team_id(npc.glow_color)))?;
+        // A player's profile name renders as nothing, so its display name
+        // goes into the prefix
+        let prefix = if npc.is_player() {
+            format!("{}{}", tag.prefix, npc.display_name)
+        } else {
+            tag.prefix.clone()
+        };
+        write_text_component(&mut buf, &text::parse(&prefix))?;
+        write_text_component(&mut buf, &text::parse(&tag.suffix))?;
+        if mode == TeamMode::Create {
+            buf.write_var_int(&VarInt(1))?;
+            buf.write_string(&team_member(npc))?;
+        }
 
         Ok(())
     })();
@@ -69,7 +188,46 @@ async fn send_team_nametag_hide(java: &pumpkin::net::java::JavaClient, npc: &Npc
     }
 }
 
-async fn send_packet<P: pumpkin_protocol::ClientPacket>(
+/// Non-player entities only show a nametag when they have a custom name.
+async fn send_custom_name(java: &pumpkin::net::java::JavaClient, npc: &Npc) -> bool {
+    let version = java.version.load();
+    let mut meta_buf = Vec::new();
+    let name = Metadata::new(
+        TrackedData::DATA_CUSTOM_NAME,
+        MetaDataType::OptionalTextComponent,
+        Some(text::parse(&npc.display_name)),
+    );
+    if let Err(e) = name.write(&mut meta_buf, &version) {
+        log::error!("Failed to write NPC name: {e:?}");
+        return false;
+    }
+    let visible = Metadata::new(
+        TrackedData::DATA_NAME_VISIBLE,
+        MetaDataType::Boolean,
+        npc.nametag.visible,
+    );
+    if let Err(e) = visible.write(&mut meta_buf, &version) {
+        log::error!("Failed to write NPC name visibility: {e:?}");
+        return false;
+    }
+    meta_buf.put_u8(0xFF);
+    let packet = CSetEntityMetadata::new(VarInt(npc.entity_id), meta_buf.into_boxed_slice());
+    send_packet(java, &packet).await
+}
+
+/// Resends the team of an NPC the player already sees, e.g. after its glow
+/// color or nametag changed.
+pub async fn update_team_for_player(npc: &Npc, player: &Arc<Player>) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+    if !npc.is_player() && !send_custom_name(java, npc).await {
+        return;
+    }
+    send_team(java, npc, TeamMode::Update).await;
+}
+
+pub(super) async fn send_packet<P: pumpkin_protocol::ClientPacket>(
     java: &pumpkin::net::java::JavaClient,
     packet: &P,
 ) -> bool {
@@ -86,11 +244,346 @@ async fn send_packet<P: pumpkin_protocol::ClientPacket>(
     }
 }
 
-pub async fn spawn_npc_for_player(npc: &Npc, player: &Arc<Player>) {
+/// The entity position sync packet was added in 1.21.2, which turned the
+/// old absolute teleport packet into a relative one.
+const POSITION_SYNC_PROTOCOL: u32 = 768;
+
+/// Teleports a spawned entity, with the packet the client's version expects.
+pub(super) async fn send_teleport(
+    java: &JavaClient,
+    entity_id: i32,
+    position: Vector3<f64>,
+    yaw: f32,
+    pitch: f32,
+    on_ground: bool,
+) -> bool {
+    let version = java.version.load();
+    if version.protocol_version() >= POSITION_SYNC_PROTOCOL {
+        let velocity = Vector3::new(0.0, 0.0, 0.0);
+        let packet =
+            CEntityPositionSync::new(VarInt(entity_id), position, velocity, yaw, pitch, on_ground);
+        return send_packet(java, &packet).await;
+    }
+
+    let packet_id = pumpkin_data::packet::clientbound::PLAY_TELEPORT_ENTITY.to_id(version);
+    let angle = |degrees: f32| (degrees.rem_euclid(360.0) * 256.0 / 360.0).floor() as u8;
+
+    let mut buf: Vec<u8> = Vec::new();
+    let result: Result<(), pumpkin_protocol::ser::WritingError> = (|| {
+        buf.write_var_int(&VarInt(packet_id))?;
+        buf.write_var_int(&VarInt(entity_id))?;
+        buf.write_f64_be(position.x)?;
+        buf.write_f64_be(position.y)?;
+        buf.write_f64_be(position.z)?;
+        buf.write_u8(angle(yaw))?;
+        buf.write_u8(angle(pitch))?;
+        buf.write_u8(u8::from(on_ground))?;
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => {
+            java.enqueue_packet_data(buf.into()).await;
+            true
+        }
+        Err(e) => {
+            log::error!("Failed to write teleport packet: {e:?}");
+            false
+        }
+    }
+}
+
+/// Entity flags bit that draws the glowing outline.
+const FLAG_GLOWING: u8 = 0x40;
+
+fn entity_flags(npc: &Npc, selected: bool) -> u8 {
+    let glow = if selected || npc.glowing {
+        FLAG_GLOWING
+    } else {
+        0
+    };
+    npc.pose.entity_flags() | glow
+}
+
+async fn is_selected_by(npc: &Npc, player: &Player) -> bool {
+    match crate::REGISTRY.get() {
+        Some(registry) => registry.get_selected(&player.gameprofile.id).await == Some(npc.id),
+        None => false,
+    }
+}
+
+/// Sends the flags and pose metadata, which depend on the NPC's pose and on
+/// whether the receiving player has it selected.
+async fn send_state(java: &pumpkin::net::java::JavaClient, npc: &Npc, selected: bool) -> bool {
+    let version = java.version.load();
+    let mut meta_buf = Vec::new();
+    let flags = Metadata::new(
+        TrackedData::DATA_FLAGS,
+        MetaDataType::Byte,
+        entity_flags(npc, selected),
+    );
+    if let Err(e) = flags.write(&mut meta_buf, &version) {
+        log::error!("Failed to write NPC flags: {e:?}");
+        return false;
+    }
+    let pose = Metadata::new(
+        TrackedData::DATA_POSE,
+        MetaDataType::EntityPose,
+        npc.pose.entity_pose(),
+    );
+    if let Err(e) = pose.write(&mut meta_buf, &version) {
+        log::error!("Failed to write NPC pose: {e:?}");
+        return false;
+    }
+    // Only players are known to be living entities; the spin attack is a
+    // player animation anyway
+    if npc.is_player() {
+        let living_flags = Metadata::new(
+            TrackedData::DATA_LIVING_FLAGS,
+            MetaDataType::Byte,
+            npc.pose.living_flags(),
+        );
+        if let Err(e) = living_flags.write(&mut meta_buf, &version) {
+            log::error!("Failed to write NPC living flags: {e:?}");
+            return false;
+        }
+    }
+    meta_buf.put_u8(0xFF);
+    let packet = CSetEntityMetadata::new(VarInt(npc.entity_id), meta_buf.into_boxed_slice());
+    send_packet(java, &packet).await
+}
+
+/// Resends the flags of an NPC the player already sees, e.g. after its glow
+/// was toggled.
+pub async fn update_state_for_player(npc: &Npc, player: &Arc<Player>) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+    let selected = is_selected_by(npc, player).await;
+    send_state(java, npc, selected).await;
+}
+
+/// Shows or hides the selection outline of an NPC for a single player.
+pub async fn send_selection_glow(npc: &Npc, player: &Arc<Player>, selected: bool) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+    send_state(java, npc, selected).await;
+}
+
+async fn send_skin_options(java: &pumpkin::net::java::JavaClient, npc: &Npc) -> bool {
+    let version = java.version.load();
+    let mut meta_buf = Vec::new();
+    let layers = Metadata::new(
+        TrackedData::DATA_PLAYER_MODE_CUSTOMIZATION_ID,
+        MetaDataType::Byte,
+        npc.skin_layers.mask(),
+    );
+    if let Err(e) = layers.write(&mut meta_buf, &version) {
+        log::error!("Failed to write NPC metadata: {e:?}");
+        return false;
+    }
+    let main_arm = Metadata::new(
+        TrackedData::DATA_MAIN_ARM,
+        MetaDataType::Byte,
+        npc.main_hand.protocol_id(),
+    );
+    if let Err(e) = main_arm.write(&mut meta_buf, &version) {
+        log::error!("Failed to write NPC main arm: {e:?}");
+        return false;
+    }
+    meta_buf.put_u8(0xFF);
+    let packet = CSetEntityMetadata::new(VarInt(npc.entity_id), meta_buf.into_boxed_slice());
+    send_packet(java, &packet).await
+}
+
+/// The scale attribute was added in 1.20.5.
+const SCALE_PROTOCOL: u32 = 766;
+
+/// Whether the client knows the scale attribute. Older clients draw every
+/// NPC at normal size.
+pub fn supports_scale(java: &JavaClient) -> bool {
+    java.version.load().protocol_version() >= SCALE_PROTOCOL
+}
+
+/// Sends the scale attribute. Clients that don't support it are skipped and
+/// simply see the NPC at normal size.
+async fn send_scale(java: &JavaClient, npc: &Npc) -> bool {
+    if !supports_scale(java) {
+        return true;
+    }
+
+    let version = java.version.load();
+    let packet_id = pumpkin_data::packet::clientbound::PLAY_UPDATE_ATTRIBUTES.to_id(version);
+
+    let mut buf: Vec<u8> = Vec::new();
+    let result: Result<(), pumpkin_protocol::ser::WritingError> = (|| {
+        buf.write_var_int(&VarInt(packet_id))?;
+        buf.write_var_int(&VarInt(npc.entity_id))?;
+        buf.write_var_int(&VarInt(1))?;
+        buf.write_var_int(&VarInt(i32::from(Attributes::SCALE.id)))?;
+        buf.write_f64_be(npc.scale)?;
+        buf.write_var_int(&VarInt(0))?; // modifiers
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => {
+            java.enqueue_packet_data(buf.into()).await;
+            true
+        }
+        Err(e) => {
+            log::error!("Failed to write attributes packet: {e:?}");
+            false
+        }
+    }
+}
+
+/// Applies a changed scale to an NPC the player already sees and moves its
+/// holograms to match.
+pub async fn update_scale_for_player(npc: &Npc, player: &Arc<Player>) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+    if !supports_scale(java) {
+        return;
+    }
+    if send_scale(java, npc).await {
+        hologram::move_holograms(java, npc).await;
+    }
+}
+
+/// Plays an animation on an NPC the player sees.
+pub async fn play_animation_for_player(npc: &Npc, animation: NpcAnimation, player: &Arc<Player>) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+
+    let version = java.version.load();
+    let mut buf: Vec<u8> = Vec::new();
+    let result: Result<(), pumpkin_protocol::ser::WritingError> = (|| {
+        match animation.animation_id() {
+            Some(id) => {
+                let packet_id = pumpkin_data::packet::clientbound::PLAY_ANIMATE.to_id(version);
+                buf.write_var_int(&VarInt(packet_id))?;
+                buf.write_var_int(&VarInt(npc.entity_id))?;
+                buf.write_u8(id)?;
+            }
+            None => {
+                let packet_id =
+                    pumpkin_data::packet::clientbound::PLAY_HURT_ANIMATION.to_id(version);
+                buf.write_var_int(&VarInt(packet_id))?;
+                buf.write_var_int(&VarInt(npc.entity_id))?;
+                buf.write_f32_be(0.0)?; // direction the hit came from
+            }
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => java.enqueue_packet_data(buf.into()).await,
+        Err(e) => log::error!("Failed to write animation packet: {e:?}"),
+    }
+}
+
+/// Resends the skin layers and main hand of an NPC the player already sees.
+pub async fn update_skin_options_for_player(npc: &Npc, player: &Arc<Player>) {
     let ClientPlatform::Java(java) = &player.client else {
         return;
     };
+    send_skin_options(java, npc).await;
+}
 
+/// Spawns the invisible seat a sitting NPC rides and mounts the NPC on it.
+/// Riders sit 0.6 blocks below the seat, so the NPC ends up sitting on the
+/// block it is placed on.
+async fn spawn_seat(java: &pumpkin::net::java::JavaClient, npc: &Npc) -> bool {
+    let seat_uuid = Uuid::new_v5(&Uuid::NAMESPACE_DNS, format!("seat:{}", npc.id).as_bytes());
+    let position = Vector3::new(npc.location.x, npc.location.y, npc.location.z);
+    let velocity = Vector3::new(0.0, 0.0, 0.0);
+    let packet = CSpawnEntity::new(
+        VarInt(npc.seat_entity_id),
+        seat_uuid,
+        VarInt(i32::from(EntityType::ARMOR_STAND.id)),
+        position,
+        0.0,
+        npc.location.yaw,
+        npc.location.yaw,
+        VarInt(0),
+        velocity,
+    );
+    if !send_packet(java, &packet).await {
+        return false;
+    }
+
+    let version = java.version.load();
+    let mut meta_buf = Vec::new();
+    let flags = Metadata::new(TrackedData::DATA_FLAGS, MetaDataType::Byte, 0x20u8);
+    if let Err(e) = flags.write(&mut meta_buf, &version) {
+        log::error!("Failed to write seat flags: {e:?}");
+        return false;
+    }
+    let gravity = Metadata::new(TrackedData::DATA_NO_GRAVITY, MetaDataType::Boolean, true);
+    if let Err(e) = gravity.write(&mut meta_buf, &version) {
+        log::error!("Failed to write seat gravity: {e:?}");
+        return false;
+    }
+    // Marker: no hitbox, so the seat can't be clicked instead of the NPC
+    let stand_flags = Metadata::new(
+        TrackedData::DATA_ARMOR_STAND_FLAGS,
+        MetaDataType::Byte,
+        16u8,
+    );
+    if let Err(e) = stand_flags.write(&mut meta_buf, &version) {
+        log::error!("Failed to write seat stand flags: {e:?}");
+        return false;
+    }
+    meta_buf.put_u8(0xFF);
+    let packet = CSetEntityMetadata::new(VarInt(npc.seat_entity_id), meta_buf.into_boxed_slice());
+    if !send_packet(java, &packet).await {
+        return false;
+    }
+
+    let passengers = [VarInt(npc.entity_id)];
+    send_packet(
+        java,
+        &CSetPassengers::new(VarInt(npc.seat_entity_id), &passengers),
+    )
+    .await
+}
+
+/// Switches an NPC that is already spawned for the player from the `old` pose
+/// to its current one.
+pub async fn update_pose_for_player(npc: &Npc, old: NpcPose, player: &Arc<Player>) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+
+    let selected = is_selected_by(npc, player).await;
+    if !send_state(java, npc, selected).await {
+        return;
+    }
+
+    let sitting = npc.pose == NpcPose::Sitting;
+    if old == NpcPose::Sitting && !sitting {
+        // Removing the seat dismounts the NPC; put it back on its feet
+        send_packet(java, &CRemoveEntities::new(&[VarInt(npc.seat_entity_id)])).await;
+        let position = Vector3::new(npc.location.x, npc.location.y, npc.location.z);
+        let (yaw, pitch) = (npc.location.yaw, npc.location.pitch);
+        send_teleport(java, npc.entity_id, position, yaw, pitch, true).await;
+    } else if old != NpcPose::Sitting && sitting && !spawn_seat(java, npc).await {
+        return;
+    }
+
+    hologram::move_holograms(java, npc).await;
+}
+
+/// Sends the player info entry of a player NPC: its profile and skin when
+/// `add` is set, and its tab list presence in any case.
+/// The tab list order was added in 1.21.2.
+const LIST_ORDER_PROTOCOL: u32 = 768;
+
+async fn send_player_info(java: &pumpkin::net::java::JavaClient, npc: &Npc, add: bool) -> bool {
     let properties = if let Some(ref skin) = npc.skin {
         vec![pumpkin_protocol::Property {
             name: "textures".to_string(),
@@ -101,32 +594,161 @@ pub async fn spawn_npc_for_player(npc: &Npc, player: &Arc<Player>) {
         vec![]
     };
 
-    let protocol_name: String = npc.name.chars().take(16).collect();
-    let actions = (PlayerInfoFlags::ADD_PLAYER | PlayerInfoFlags::UPDATE_LISTED).bits();
+    let protocol_name = npc.profile_name();
+    let tab = &npc.tab;
+    // The profile name renders as nothing, so always send a display name
+    let display = text::parse(tab.display_name.as_deref().unwrap_or(&npc.display_name));
+
+    let mut flags = PlayerInfoFlags::UPDATE_GAME_MODE
+        | PlayerInfoFlags::UPDATE_LISTED
+        | PlayerInfoFlags::UPDATE_LATENCY
+        | PlayerInfoFlags::UPDATE_DISPLAY_NAME;
+    // Older clients don't know the action and sort by team name only
+    let list_order = java.version.load().protocol_version() >= LIST_ORDER_PROTOCOL;
+    if list_order {
+        flags |= PlayerInfoFlags::UPDATE_LIST_ORDER;
+    }
+    let mut actions = Vec::new();
+    if add {
+        flags |= PlayerInfoFlags::ADD_PLAYER;
+        actions.push(PlayerAction::AddPlayer {
+            name: &protocol_name,
+            properties: &properties,
+        });
+    }
+    actions.push(PlayerAction::UpdateGameMode(VarInt(
+        tab.game_mode.protocol_id(),
+    )));
+    actions.push(PlayerAction::UpdateListed(tab.listed));
+    actions.push(PlayerAction::UpdateLatency(VarInt(tab.latency)));
+    actions.push(PlayerAction::UpdateDisplayName(Some(display)));
+    if list_order {
+        actions.push(PlayerAction::UpdateListOrder(VarInt(tab.order)));
+    }
+
     let protocol_player = ProtocolPlayer {
         uuid: npc.uuid,
-        actions: &[
-            PlayerAction::AddPlayer {
-                name: &protocol_name,
-                properties: &properties,
-            },
-            PlayerAction::UpdateListed(false),
-        ],
+        actions: &actions,
+    };
+    let players = [protocol_player];
+    let packet = CPlayerInfoUpdate::new(flags.bits(), &players);
+    send_packet(java, &packet).await
+}
+
+/// Applies a changed tab list entry of a player NPC the player already sees,
+/// without respawning it. `old` is the NPC before the change.
+pub async fn update_tab_for_player(old: &Npc, npc: &Npc, player: &Arc<Player>) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
     };
-    {
-        let players = [protocol_player];
-        let packet = CPlayerInfoUpdate::new(actions, &players);
-        if !send_packet(java, &packet).await {
+    if old.tab.group != npc.tab.group {
+        send_team(java, old, TeamMode::Remove).await;
+        if !send_team(java, npc, TeamMode::Create).await {
             return;
         }
     }
+    send_player_info(java, npc, false).await;
+}
+
+/// Bit of the tameable flags byte that marks a cat or wolf as tamed.
+const TAMED_FLAG: u8 = 0x04;
+
+/// Writes the metadata specific to non-player entity types: baby flag,
+/// villager data and colors. Data the type doesn't support is skipped.
+async fn send_type_data(java: &pumpkin::net::java::JavaClient, npc: &Npc) -> bool {
+    #[derive(serde::Serialize)]
+    struct VillagerDataValue(VarInt, VarInt, VarInt);
+
+    let version = java.version.load();
+    let mut meta_buf = Vec::new();
+    let data = &npc.entity_data;
+    let short = npc
+        .entity_type
+        .strip_prefix("minecraft:")
+        .unwrap_or(&npc.entity_type);
+
+    let result = (|| {
+        if data.baby && short == "armor_stand" {
+            Metadata::new(
+                TrackedData::DATA_ARMOR_STAND_FLAGS,
+                MetaDataType::Byte,
+                0x01u8,
+            )
+            .write(&mut meta_buf, &version)?;
+        } else if data.baby && entity::supports_baby(&npc.entity_type) {
+            Metadata::new(TrackedData::DATA_BABY, MetaDataType::Boolean, true)
+                .write(&mut meta_buf, &version)?;
+        }
+
+        if let Some(villager) = data
+            .villager
+            .as_ref()
+            .filter(|_| entity::supports_villager_data(&npc.entity_type))
+        {
+            let (biome, profession) = villager.protocol_ids();
+            Metadata::new(
+                TrackedData::DATA_VILLAGER_DATA,
+                MetaDataType::VillagerData,
+                VillagerDataValue(VarInt(biome), VarInt(profession), VarInt(villager.level)),
+            )
+            .write(&mut meta_buf, &version)?;
+        }
+
+        if let Some(color) = data
+            .color
+            .as_deref()
+            .filter(|_| entity::supports_color(&npc.entity_type))
+        {
+            let id = entity::color_id(color);
+            if short == "sheep" {
+                Metadata::new(TrackedData::DATA_COLOR, MetaDataType::Byte, id as u8)
+                    .write(&mut meta_buf, &version)?;
+            } else {
+                // The client only draws a collar on tamed animals
+                Metadata::new(
+                    TrackedData::DATA_TAMEABLE_FLAGS,
+                    MetaDataType::Byte,
+                    TAMED_FLAG,
+                )
+                .write(&mut meta_buf, &version)?;
+                Metadata::new(
+                    TrackedData::DATA_COLLAR_COLOR,
+                    MetaDataType::Integer,
+                    VarInt(id),
+                )
+                .write(&mut meta_buf, &version)?;
+            }
+        }
+        Ok::<(), pumpkin_protocol::ser::WritingError>(())
+    })();
+
+    if let Err(e) = result {
+        log::error!("Failed to write NPC entity data: {e:?}");
+        return false;
+    }
+    if meta_buf.is_empty() {
+        return true;
+    }
+    meta_buf.put_u8(0xFF);
+    let packet = CSetEntityMetadata::new(VarInt(npc.entity_id), meta_buf.into_boxed_slice());
+    send_packet(java, &packet).await
+}
+
+pub async fn spawn_npc_for_player(npc: &Npc, player: &Arc<Player>) {
+    let ClientPlatform::Java(java) = &player.client else {
+        return;
+    };
+
+    if npc.is_player() && !send_player_info(java, npc, true).await {
+        return;
+    }
 
     let position = Vector3::new(npc.location.x, npc.location.y, npc.location.z);
     let velocity = Vector3::new(0.0, 0.0, 0.0);
     let packet = CSpawnEntity::new(
         VarInt(npc.entity_id),
         npc.uuid,
-        VarInt(i32::from(EntityType::PLAYER.id)),
+        VarInt(i32::from(entity::lookup(&npc.entity_type).id)),
         position,
         npc.location.pitch,
         npc.location.yaw,
@@ -143,30 +765,38 @@ pub async fn spawn_npc_for_player(npc: &Npc, player: &Arc<Player>) {
         return;
     }
 
-    {
-        let version = java.version.load();
-        let mut meta_buf = Vec::new();
-        let meta = Metadata::new(
-            TrackedData::DATA_PLAYER_MODE_CUSTOMIZATION_ID,
-            MetaDataType::Byte,
-            0x7Fu8,
-        );
-        if let Err(e) = meta.write(&mut meta_buf, &version) {
-            log::error!("Failed to write NPC metadata: {e:?}");
-            return;
-        }
-        meta_buf.put_u8(0xFF);
-        let packet = CSetEntityMetadata::new(VarInt(npc.entity_id), meta_buf.into_boxed_slice());
-        if !send_packet(java, &packet).await {
-            return;
-        }
+    let sent = if npc.is_player() {
+        send_skin_options(java, npc).await
+    } else {
+        send_type_data(java, npc).await && send_custom_name(java, npc).await
+    };
+    if !sent {
+        return;
+    }
+
+    let selected = is_selected_by(npc, player).await;
+    if !send_state(java, npc, selected).await {
+        return;
+    }
+
+    if npc.scale != 1.0 && !send_scale(java, npc).await {
+        return;
+    }
+
+    if npc.pose == NpcPose::Sitting && !spawn_seat(java, npc).await {
+        return;
+    }
+
+    let equipped: Vec<EquipmentSlot> = npc.equipment.keys().copied().collect();
+    if !send_equipment(java, npc, &equipped).await {
+        return;
     }
 
-    if !send_team_nametag_hide(java, npc).await {
+    if !send_team(java, npc, TeamMode::Create).await {
         return;
     }
 
-    spawn_holograms_for_player(npc, player).await;
+    hologram::spawn_holograms_for_player(npc, player).await;
 }
 
 pub async fn update_npc_look_at_player(npc: &Npc, player: &Arc<Player>, target_pos: &Vector3<f64>) {
@@ -193,111 +823,47 @@ pub async fn update_npc_look_at_player(npc: &Npc, player: &Arc<Player>, target_p
     .await;
 }
 
-pub async fn spawn_holograms_for_player(npc: &Npc, player: &Arc<Player>) {
-    let total = npc.holograms.len();
-    for (i, line) in npc.holograms.iter().enumerate() {
-        let y = npc.location.y + 2.05 + ((total - 1 - i) as f64 * 0.25);
-        spawn_hologram_entity(npc, line, y, player).await;
-    }
-}
-
-pub async fn despawn_holograms_for_player(npc: &Npc, player: &Arc<Player>) {
-    if npc.holograms.is_empty() {
-        return;
-    }
-    let ClientPlatform::Java(java) = &player.client else {
-        return;
-    };
-    let entity_ids: Vec<VarInt> = npc.holograms.iter().map(|h| VarInt(h.entity_id)).collect();
-    send_packet(java, &CRemoveEntities::new(&entity_ids)).await;
-}
-
-async fn spawn_hologram_entity(npc: &Npc, line: &HologramLine, y: f64, player: &Arc<Player>) {
+/// Moves an already spawned NPC and its hologram stack to the NPC's current
+/// location and rotation.
+pub async fn teleport_npc_for_player(npc: &Npc, player: &Arc<Player>) {
     let ClientPlatform::Java(java) = &player.client else {
         return;
     };
 
-    let holo_uuid = Uuid::new_v5(
-        &Uuid::NAMESPACE_DNS,
-        format!("hologram:{}", line.entity_id).as_bytes(),
-    );
-
-    let position = Vector3::new(npc.location.x, y, npc.location.z);
-    let velocity = Vector3::new(0.0, 0.0, 0.0);
-    let packet = CSpawnEntity::new(
-        VarInt(line.entity_id),
-        holo_uuid,
-        VarInt(i32::from(EntityType::ARMOR_STAND.id)),
-        position,
-        0.0,
-        0.0,
-        0.0,
-        VarInt(0),
-        velocity,
-    );
-    if !send_packet(java, &packet).await {
-        return;
-    }
-
-    let version = java.version.load();
-    let mut meta_buf = Vec::new();
-
-    let flags = Metadata::new(TrackedData::DATA_FLAGS, MetaDataType::Byte, 0x20u8);
-    if let Err(e) = flags.write(&mut meta_buf, &version) {
-        log::error!("Failed to write hologram flags: {e:?}");
-        return;
-    }
-
-    let display_text = if let Some(ref server_name) = npc.server {
-        crate::status::resolve_placeholders(&line.text, server_name)
+    let position = Vector3::new(npc.location.x, npc.location.y, npc.location.z);
+    // A sitting NPC follows its seat
+    let moved_id = if npc.pose == NpcPose::Sitting {
+        npc.seat_entity_id
     } else {
-        line.text.clone()
+        npc.entity_id
     };
-    let name = Metadata::new(
-        TrackedData::DATA_CUSTOM_NAME,
-        MetaDataType::OptionalTextComponent,
-        Some(TextComponent::text(display_text)),
-    );
-    if let Err(e) = name.write(&mut meta_buf, &version) {
-        log::error!("Failed to write hologram name: {e:?}");
-        return;
-    }
-
-    let visible = Metadata::new(TrackedData::DATA_NAME_VISIBLE, MetaDataType::Boolean, true);
-    if let Err(e) = visible.write(&mut meta_buf, &version) {
-        log::error!("Failed to write hologram visibility: {e:?}");
-        return;
-    }
-
-    let gravity = Metadata::new(TrackedData::DATA_NO_GRAVITY, MetaDataType::Boolean, true);
-    if let Err(e) = gravity.write(&mut meta_buf, &version) {
-        log::error!("Failed to write hologram gravity: {e:?}");
+    let (yaw, pitch) = (npc.location.yaw, npc.location.pitch);
+    if !send_teleport(java, moved_id, position, yaw, pitch, true).await {
         return;
     }
 
-    let stand_flags = Metadata::new(
-        TrackedData::DATA_ARMOR_STAND_FLAGS,
-        MetaDataType::Byte,
-        16u8,
-    );
-    if let Err(e) = stand_flags.write(&mut meta_buf, &version) {
-        log::error!("Failed to write hologram stand flags: {e:?}");
+    let yaw_byte = (npc.location.yaw.rem_euclid(360.0) * 256.0 / 360.0).floor() as u8;
+    if !send_packet(java, &CHeadRot::new(VarInt(npc.entity_id), yaw_byte)).await {
         return;
     }
 
-    meta_buf.put_u8(0xFF);
-    let packet = CSetEntityMetadata::new(VarInt(line.entity_id), meta_buf.into_boxed_slice());
-    send_packet(java, &packet).await;
+    hologram::move_holograms(java, npc).await;
 }
 
 pub async fn despawn_npc_for_player(npc: &Npc, player: &Arc<Player>) {
     let mut entity_ids = vec![VarInt(npc.entity_id)];
-    for line in &npc.holograms {
-        entity_ids.push(VarInt(line.entity_id));
+    if npc.pose == NpcPose::Sitting {
+        entity_ids.push(VarInt(npc.seat_entity_id));
+    }
+    if !npc.holograms.is_empty() {
+        entity_ids.extend(hologram::entity_ids(npc));
     }
     let ClientPlatform::Java(java) = &player.client else {
         return;
     };
     send_packet(java, &CRemoveEntities::new(&entity_ids)).await;
-    send_packet(java, &CRemovePlayerInfo::new(&[npc.uuid])).await;
+    send_team(java, npc, TeamMode::Remove).await;
+    if npc.is_player() {
+        send_packet(java, &CRemovePlayerInfo::new(&[npc.uuid])).await;
+    }
 }
@@ -0,0 +1,127 @@
+use std::fmt;
+use std::str::FromStr;
+
+use pumpkin_data::entity::EntityPose;
+use serde::{Deserialize, Serialize};
+
+/// Body pose an NPC is shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NpcPose {
+    #[default]
+    Standing,
+    Crouching,
+    Sleeping,
+    Swimming,
+    SpinAttack,
+    /// Riding an invisible seat entity.
+    Sitting,
+}
+
+impl NpcPose {
+    pub const ALL: [NpcPose; 6] = [
+        NpcPose::Standing,
+        NpcPose::Crouching,
+        NpcPose::Sleeping,
+        NpcPose::Swimming,
+        NpcPose::SpinAttack,
+        NpcPose::Sitting,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NpcPose::Standing => "standing",
+            NpcPose::Crouching => "crouching",
+            NpcPose::Sleeping => "sleeping",
+            NpcPose::Swimming => "swimming",
+            NpcPose::SpinAttack => "spin_attack",
+            NpcPose::Sitting => "sitting",
+        }
+    }
+
+    /// Value of the entity pose metadata field. Sitting is a standing entity
+    /// that rides a seat.
+    pub fn entity_pose(self) -> EntityPose {
+        match self {
+            NpcPose::Standing | NpcPose::Sitting => EntityPose::Standing,
+            NpcPose::Crouching => EntityPose::Crouching,
+            NpcPose::Sleeping => EntityPose::Sleeping,
+            NpcPose::Swimming => EntityPose::Swimming,
+            NpcPose::SpinAttack => EntityPose::SpinAttack,
+        }
+    }
+
+    /// Bits of the shared entity flags byte the client also checks for the pose.
+    pub fn entity_flags(self) -> u8 {
+        match self {
+            NpcPose::Crouching => 0x02,
+            NpcPose::Swimming => 0x10,
+            _ => 0,
+        }
+    }
+
+    /// Bits of the living entity flags byte; the riptide spin is driven from here.
+    pub fn living_flags(self) -> u8 {
+        match self {
+            NpcPose::SpinAttack => 0x04,
+            _ => 0,
+        }
+    }
+
+    /// Height of the top of the model above the NPC's location.
+    pub fn height(self) -> f64 {
+        match self {
+            NpcPose::Standing => 1.8,
+            NpcPose::Crouching => 1.5,
+            NpcPose::Sleeping | NpcPose::Swimming | NpcPose::SpinAttack => 0.6,
+            // Riders sit 0.6 below the seat, which is placed at the location
+            NpcPose::Sitting => 1.2,
+        }
+    }
+}
+
+impl fmt::Display for NpcPose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NpcPose {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NpcPose::ALL
+            .into_iter()
+            .find(|pose| pose.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown pose '{s}' (expected standing, crouching, sleeping, swimming, spin_attack or sitting)"
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_pose_by_name() {
+        for pose in NpcPose::ALL {
+            assert_eq!(pose.as_str().parse::<NpcPose>(), Ok(pose));
+        }
+    }
+
+    #[test]
+    fn parsing_ignores_case() {
+        assert_eq!("SITTING".parse::<NpcPose>(), Ok(NpcPose::Sitting));
+        assert_eq!("Spin_Attack".parse::<NpcPose>(), Ok(NpcPose::SpinAttack));
+    }
+
+    #[test]
+    fn rejects_unknown_poses() {
+        assert!("lying".parse::<NpcPose>().is_err());
+        assert!("spin attack".parse::<NpcPose>().is_err());
+        assert!("".parse::<NpcPose>().is_err());
+    }
+}
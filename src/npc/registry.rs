@@ -1,27 +1,98 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
-use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, Notify, RwLock};
+use tokio::time::{sleep, Duration};
 use uuid::Uuid;
 
+use super::storage::{self, NpcStorage};
 use super::{HologramLine, Npc, NpcLocation, NpcSkin};
-use crate::DATA_FOLDER;
 
-const NPCS_FILE: &str = "npcs.toml";
+/// Difference between the in-memory NPCs and storage after a reload.
+#[derive(Default)]
+pub struct ReloadDiff {
+    pub added: Vec<Npc>,
+    pub removed: Vec<Npc>,
+    /// Old and new version of each NPC whose stored fields changed.
+    pub changed: Vec<(Npc, Npc)>,
+}
+
+/// Compares only the persisted fields, ignoring runtime IDs.
+fn same_stored(a: &Npc, b: &Npc) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn check_key(npcs: &HashMap<u32, Npc>, key: &str, owner: Option<u32>) -> Result<(), String> {
+    if !super::is_valid_key(key) {
+        return Err(format!(
+            "Invalid key '{key}': use up to 32 lowercase letters, digits, '_' or '-', not only digits"
+        ));
+    }
+    if npcs.values().any(|n| n.key == key && Some(n.id) != owner) {
+        return Err(format!("Key '{key}' is already used by another NPC"));
+    }
+    Ok(())
+}
 
-fn npcs_path() -> PathBuf {
-    DATA_FOLDER
-        .get()
-        .expect("Data folder not initialized")
-        .join(NPCS_FILE)
+/// `base`, or `base_2`, `base_3`, ... if it's taken.
+fn unique_key(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{base}_{n}"))
+        .find(|key| !taken(key))
+        .expect("unbounded range")
+}
+
+/// Gives loaded NPCs whose key is invalid or already used by an NPC with a
+/// lower ID, e.g. after a hand edit, a new key derived from their name like
+/// `create` does. Returns the IDs of the NPCs that got a new key.
+fn fix_keys(loaded: &mut [Npc]) -> Vec<u32> {
+    loaded.sort_by_key(|n| n.id);
+    let mut taken = HashSet::new();
+    let mut broken = Vec::new();
+    for (i, npc) in loaded.iter().enumerate() {
+        if !super::is_valid_key(&npc.key) || !taken.insert(npc.key.clone()) {
+            broken.push(i);
+        }
+    }
+
+    let mut renamed = Vec::with_capacity(broken.len());
+    for i in broken {
+        let npc = &mut loaded[i];
+        let key = unique_key(&super::key_from_name(&npc.display_name), |key| {
+            taken.contains(key)
+        });
+        let problem = if super::is_valid_key(&npc.key) {
+            "is already used by another NPC"
+        } else {
+            "is not a valid key"
+        };
+        log::warn!(
+            "Key '{}' of NPC #{} {problem}, renamed it to '{key}'",
+            npc.key,
+            npc.id
+        );
+        taken.insert(key.clone());
+        npc.key = key;
+        renamed.push(npc.id);
+    }
+    renamed
 }
 
-#[derive(Serialize, Deserialize)]
-struct NpcConfig {
-    #[serde(default)]
-    npcs: Vec<Npc>,
+/// Edits made since the last write.
+#[derive(Default)]
+struct PendingChanges {
+    changed: HashSet<u32>,
+    removed: HashSet<u32>,
+}
+
+impl PendingChanges {
+    fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
 }
 
 #[allow(dead_code)]
@@ -29,89 +100,269 @@ pub struct NpcRegistry {
     npcs: RwLock<HashMap<u32, Npc>>,
     next_id: AtomicU32,
     selected: RwLock<HashMap<Uuid, u32>>,
+    storage: Arc<dyn NpcStorage>,
+    pending: std::sync::Mutex<PendingChanges>,
+    changed: Notify,
+    /// Serializes writes so an older snapshot can never land after a newer one.
+    write_lock: Mutex<()>,
 }
 
 #[allow(dead_code)]
 impl NpcRegistry {
-    pub fn new() -> Self {
+    pub fn new(storage: Arc<dyn NpcStorage>) -> Self {
         Self {
             npcs: RwLock::new(HashMap::new()),
             next_id: AtomicU32::new(1),
             selected: RwLock::new(HashMap::new()),
+            storage,
+            pending: std::sync::Mutex::new(PendingChanges::default()),
+            changed: Notify::new(),
+            write_lock: Mutex::new(()),
         }
     }
 
-    pub async fn load(&self) -> usize {
-        let path = npcs_path();
-        if !path.exists() {
-            return 0;
-        }
-        let contents = match std::fs::read_to_string(&path) {
-            Ok(c) => c,
-            Err(e) => {
-                log::error!("Failed to read {NPCS_FILE}: {e}");
-                return 0;
+    pub fn storage(&self) -> &dyn NpcStorage {
+        self.storage.as_ref()
+    }
+
+    /// Spawns the background task that writes NPCs to storage after edits.
+    /// Edits made within `save_delay_ms` of each other are written together.
+    pub fn start_writer(self: &Arc<Self>) {
+        let registry = Arc::clone(self);
+        let delay = Duration::from_millis(crate::config::config().save_delay_ms);
+        tokio::spawn(async move {
+            loop {
+                registry.changed.notified().await;
+                sleep(delay).await;
+                registry.save().await;
             }
-        };
-        let mut config: NpcConfig = match toml::from_str(&contents) {
-            Ok(c) => c,
+        });
+    }
+
+    /// Writes pending changes immediately. Called on shutdown.
+    pub async fn flush(&self) {
+        self.save().await;
+    }
+
+    fn mark_changed(&self, id: u32) {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            pending.removed.remove(&id);
+            pending.changed.insert(id);
+        }
+        self.changed.notify_one();
+    }
+
+    fn mark_removed(&self, id: u32) {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            pending.changed.remove(&id);
+            pending.removed.insert(id);
+        }
+        self.changed.notify_one();
+    }
+
+    pub async fn load(&self) -> usize {
+        let mut loaded = match storage::run(&self.storage, |s| s.load()).await {
+            Ok(n) => n,
             Err(e) => {
-                log::error!("Failed to parse {NPCS_FILE}: {e}");
+                log::error!("{e}");
                 return 0;
             }
         };
 
+        let renamed = fix_keys(&mut loaded);
         let mut max_id = 0u32;
-        for npc in &mut config.npcs {
+        for npc in &mut loaded {
             npc.init_runtime_fields();
             if npc.id > max_id {
                 max_id = npc.id;
             }
         }
 
-        let count = config.npcs.len();
+        let count = loaded.len();
         let mut npcs = self.npcs.write().await;
-        for npc in config.npcs {
+        for npc in loaded {
             npcs.insert(npc.id, npc);
         }
         self.next_id.store(max_id + 1, Ordering::Relaxed);
+        drop(npcs);
+        for id in renamed {
+            self.mark_changed(id);
+        }
         count
     }
 
-    async fn save(&self) {
-        let npcs = self.npcs.read().await;
-        let mut sorted: Vec<&Npc> = npcs.values().collect();
-        sorted.sort_by_key(|n| n.id);
+    /// Re-reads storage and replaces the in-memory NPCs, keeping the runtime
+    /// state of NPCs that didn't change. Edits not yet written are flushed
+    /// first; if that fails the reload is refused rather than losing them.
+    /// If storage was also changed on disk since it was last written, one
+    /// side would be lost, so the reload is refused unless `force` is set,
+    /// which drops the unsaved edits instead. On failure the current NPCs
+    /// are left as they are.
+    pub async fn reload(&self, force: bool) -> Result<ReloadDiff, String> {
+        let _guard = self.write_lock.lock().await;
+        if self.has_pending_changes()
+            && storage::run(&self.storage, |s| Ok(s.changed_on_disk())).await?
+        {
+            let name = self.storage.describe();
+            if !force {
+                return Err(format!(
+                    "NPCs were edited in game and {name} was changed on disk since it was last written. Use /npc reload force to load {name} and drop the in-game edits"
+                ));
+            }
+            log::warn!("Dropping unsaved NPC edits to reload {name}");
+            *self.pending.lock().unwrap() = PendingChanges::default();
+        }
 
-        let config = NpcConfig {
-            npcs: sorted.into_iter().cloned().collect(),
-        };
+        self.write_pending().await;
+        if self.has_pending_changes() {
+            return Err(
+                "Some NPC edits couldn't be saved yet and would be lost; not reloading NPCs"
+                    .to_string(),
+            );
+        }
+        let mut loaded = storage::run(&self.storage, |s| s.load()).await?;
+        let renamed = fix_keys(&mut loaded);
 
-        let contents = match toml::to_string_pretty(&config) {
-            Ok(c) => c,
-            Err(e) => {
-                log::error!("Failed to serialize NPCs: {e}");
-                return;
+        let mut diff = ReloadDiff::default();
+        let mut npcs = self.npcs.write().await;
+        let mut old = std::mem::take(&mut *npcs);
+
+        for mut npc in loaded {
+            match old.remove(&npc.id) {
+                Some(existing) if same_stored(&existing, &npc) => {
+                    npcs.insert(existing.id, existing);
+                }
+                Some(existing) => {
+                    npc.init_runtime_fields();
+                    npcs.insert(npc.id, npc.clone());
+                    diff.changed.push((existing, npc));
+                }
+                None => {
+                    npc.init_runtime_fields();
+                    npcs.insert(npc.id, npc.clone());
+                    diff.added.push(npc);
+                }
             }
+        }
+        diff.removed = old.into_values().collect();
+
+        if let Some(max_id) = npcs.keys().max() {
+            self.next_id.fetch_max(max_id + 1, Ordering::Relaxed);
+        }
+        drop(npcs);
+        for id in renamed {
+            self.mark_changed(id);
+        }
+
+        if !diff.removed.is_empty() {
+            self.selected
+                .write()
+                .await
+                .retain(|_, id| !diff.removed.iter().any(|n| n.id == *id));
+        }
+
+        Ok(diff)
+    }
+
+    /// Whether there are edits waiting for the background writer.
+    pub fn has_pending_changes(&self) -> bool {
+        !self.pending.lock().unwrap().is_empty()
+    }
+
+    async fn save(&self) {
+        let _guard = self.write_lock.lock().await;
+        self.write_pending().await;
+    }
+
+    /// Writes the queued edits. Callers hold `write_lock`.
+    async fn write_pending(&self) {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return;
+        }
+
+        let removed: Vec<u32> = pending.removed.iter().copied().collect();
+        let result = if self.storage.writes_single_npcs() {
+            let changed: Vec<Npc> = {
+                let npcs = self.npcs.read().await;
+                pending
+                    .changed
+                    .iter()
+                    .filter_map(|id| npcs.get(id).cloned())
+                    .collect()
+            };
+            storage::run(&self.storage, move |s| s.save_changes(&changed, &removed)).await
+        } else {
+            let all: Vec<Npc> = self.npcs.read().await.values().cloned().collect();
+            storage::run(&self.storage, move |s| s.save_all(&all)).await
         };
 
-        if let Err(e) = std::fs::write(npcs_path(), contents) {
-            log::error!("Failed to write {NPCS_FILE}: {e}");
+        if let Err(e) = result {
+            log::error!("{e}");
+            // Keep the edits queued so the next write retries them, without
+            // clobbering anything that happened since
+            let mut current = self.pending.lock().unwrap();
+            for id in pending.changed {
+                if !current.removed.contains(&id) {
+                    current.changed.insert(id);
+                }
+            }
+            for id in pending.removed {
+                if !current.changed.contains(&id) {
+                    current.removed.insert(id);
+                }
+            }
         }
     }
 
-    pub async fn create(&self, name: String, location: NpcLocation, skin: Option<NpcSkin>) -> Npc {
+    /// Creates an NPC with the given key, or one derived from its name.
+    pub async fn create(
+        &self,
+        key: Option<String>,
+        display_name: String,
+        location: NpcLocation,
+        skin: Option<NpcSkin>,
+    ) -> Result<Npc, String> {
+        let mut npcs = self.npcs.write().await;
+        let key = match key {
+            Some(key) => {
+                check_key(&npcs, &key, None)?;
+                key
+            }
+            None => unique_key(&super::key_from_name(&display_name), |key| {
+                npcs.values().any(|n| n.key == key)
+            }),
+        };
+
         let id = self.next_id.fetch_add(1, Ordering::Relaxed);
-        let npc = Npc::new(id, name, location, skin);
-        self.npcs.write().await.insert(id, npc.clone());
-        self.save().await;
-        npc
+        let npc = Npc::new(id, key, display_name, location, skin);
+        npcs.insert(id, npc.clone());
+        drop(npcs);
+        self.mark_changed(id);
+        Ok(npc)
+    }
+
+    pub async fn set_key(&self, id: u32, key: String) -> Result<Npc, String> {
+        let mut npcs = self.npcs.write().await;
+        check_key(&npcs, &key, Some(id))?;
+        let npc = npcs.get_mut(&id).ok_or("NPC not found")?;
+        npc.key = key;
+        let npc = npc.clone();
+        drop(npcs);
+        self.mark_changed(id);
+        Ok(npc)
     }
 
     pub async fn remove(&self, id: u32) -> Option<Npc> {
         let npc = self.npcs.write().await.remove(&id);
         if npc.is_some() {
-            self.save().await;
+            self.mark_removed(id);
+            self.selected
+                .write()
+                .await
+                .retain(|_, selected| *selected != id);
         }
         npc
     }
@@ -120,6 +371,16 @@ impl NpcRegistry {
         self.npcs.read().await.get(&id).cloned()
     }
 
+    /// Looks an NPC up by key, falling back to a numeric ID.
+    pub async fn find(&self, query: &str) -> Option<Npc> {
+        let npcs = self.npcs.read().await;
+        if let Some(npc) = npcs.values().find(|n| n.key.eq_ignore_ascii_case(query)) {
+            return Some(npc.clone());
+        }
+        let id: u32 = query.strip_prefix('#').unwrap_or(query).parse().ok()?;
+        npcs.get(&id).cloned()
+    }
+
     pub async fn get_by_entity_id(&self, entity_id: i32) -> Option<Npc> {
         self.npcs
             .read()
@@ -133,14 +394,45 @@ impl NpcRegistry {
         self.npcs.read().await.values().cloned().collect()
     }
 
-    pub async fn select(&self, player_uuid: Uuid, npc_id: u32) {
-        self.selected.write().await.insert(player_uuid, npc_id);
+    /// Calls `f` with every NPC without cloning them, e.g. to check which
+    /// are in range of a player. No NPC can be edited until it returns.
+    pub async fn for_each(&self, f: impl FnMut(&Npc)) {
+        self.npcs.read().await.values().for_each(f);
+    }
+
+    /// Selects an NPC for a player, returning their previous selection.
+    pub async fn select(&self, player_uuid: Uuid, npc_id: u32) -> Option<u32> {
+        self.selected.write().await.insert(player_uuid, npc_id)
+    }
+
+    pub async fn deselect(&self, player_uuid: &Uuid) -> Option<u32> {
+        self.selected.write().await.remove(player_uuid)
     }
 
     pub async fn get_selected(&self, player_uuid: &Uuid) -> Option<u32> {
         self.selected.read().await.get(player_uuid).copied()
     }
 
+    /// Edits an NPC while holding the write lock, so edits made at the same
+    /// time can't undo each other. If `f` fails the NPC is left as it was.
+    /// Returns the NPC before and after the edit.
+    pub async fn update<E>(
+        &self,
+        id: u32,
+        f: impl FnOnce(&mut Npc) -> Result<(), E>,
+    ) -> Option<Result<(Npc, Npc), E>> {
+        let mut npcs = self.npcs.write().await;
+        let npc = npcs.get_mut(&id)?;
+        let mut after = npc.clone();
+        if let Err(e) = f(&mut after) {
+            return Some(Err(e));
+        }
+        let before = std::mem::replace(npc, after.clone());
+        drop(npcs);
+        self.mark_changed(id);
+        Some(Ok((before, after)))
+    }
+
     async fn modify<F, R>(&self, id: u32, f: F) -> Option<R>
     where
         F: FnOnce(&mut Npc) -> R,
@@ -149,7 +441,7 @@ impl NpcRegistry {
         let npc = npcs.get_mut(&id)?;
         let result = f(npc);
         drop(npcs);
-        self.save().await;
+        self.mark_changed(id);
         Some(result)
     }
 
@@ -161,31 +453,34 @@ impl NpcRegistry {
         .await
     }
 
-    pub async fn add_hologram(&self, id: u32, text: String) -> Option<Npc> {
-        self.modify(id, |npc| {
-            npc.holograms.push(HologramLine {
-                text,
-                entity_id: super::next_entity_id(),
-            });
-            npc.clone()
-        })
-        .await
+    pub async fn look_at_nearest_npcs(&self) -> Vec<Npc> {
+        self.npcs
+            .read()
+            .await
+            .values()
+            .filter(|n| n.look_at_nearest)
+            .cloned()
+            .collect()
     }
 
-    pub async fn set_server(&self, id: u32, server: Option<String>) -> Option<Npc> {
-        self.modify(id, |npc| {
-            npc.server = server;
-            npc.clone()
-        })
-        .await
+    /// NPCs with at least one animated hologram line.
+    pub async fn animated_npcs(&self) -> Vec<Npc> {
+        self.npcs
+            .read()
+            .await
+            .values()
+            .filter(|n| n.holograms.iter().any(|l| l.animation.is_some()))
+            .cloned()
+            .collect()
     }
 
-    pub async fn look_at_nearest_npcs(&self) -> Vec<Npc> {
+    /// NPCs with at least one spinning item or block line.
+    pub async fn spinning_npcs(&self) -> Vec<Npc> {
         self.npcs
             .read()
             .await
             .values()
-            .filter(|n| n.look_at_nearest)
+            .filter(|n| n.holograms.iter().any(HologramLine::spins))
             .cloned()
             .collect()
     }
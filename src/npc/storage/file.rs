@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::schema::{self, SCHEMA_VERSION};
+use super::NpcStorage;
+use crate::npc::Npc;
+use crate::persist;
+use crate::DATA_FOLDER;
+
+#[derive(Clone, Copy)]
+enum Format {
+    Toml,
+    Json,
+}
+
+#[derive(Serialize)]
+struct NpcConfig<'a> {
+    version: u32,
+    npcs: &'a [Npc],
+}
+
+/// Keeps every NPC in a single TOML or JSON document that is rewritten as a
+/// whole on each save.
+pub struct FileStorage {
+    format: Format,
+    file_name: &'static str,
+    /// Last contents written, so unchanged snapshots don't touch the file or
+    /// its backups.
+    last_written: Mutex<Option<String>>,
+    /// Modification time of the file when it was last loaded or written,
+    /// `None` inside if it didn't exist. `None` before the first load.
+    seen: Mutex<Option<Option<SystemTime>>>,
+    /// Set when the file on disk couldn't be read, so it is never overwritten.
+    read_only: AtomicBool,
+}
+
+impl FileStorage {
+    pub fn toml() -> Self {
+        Self::new(Format::Toml, "npcs.toml")
+    }
+
+    pub fn json() -> Self {
+        Self::new(Format::Json, "npcs.json")
+    }
+
+    fn new(format: Format, file_name: &'static str) -> Self {
+        Self {
+            format,
+            file_name,
+            last_written: Mutex::new(None),
+            seen: Mutex::new(None),
+            read_only: AtomicBool::new(false),
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        DATA_FOLDER
+            .get()
+            .expect("Data folder not initialized")
+            .join(self.file_name)
+    }
+
+    /// Parses the document into its schema version and raw NPC records.
+    fn parse(&self, contents: &str) -> Result<(u32, Vec<Value>), String> {
+        let document: Value = match self.format {
+            Format::Toml => toml::from_str::<toml::Value>(contents)
+                .map_err(|e| e.to_string())
+                .and_then(|v| serde_json::to_value(v).map_err(|e| e.to_string()))?,
+            Format::Json => serde_json::from_str(contents).map_err(|e| e.to_string())?,
+        };
+
+        // Files written before versioning have no marker
+        let version = match document.get("version") {
+            None => 1,
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or("`version` is not a number")?,
+        };
+        let npcs = match document.get("npcs") {
+            None => Vec::new(),
+            Some(Value::Array(npcs)) => npcs.clone(),
+            Some(_) => return Err("`npcs` is not a list".to_string()),
+        };
+        Ok((version, npcs))
+    }
+}
+
+impl NpcStorage for FileStorage {
+    fn describe(&self) -> String {
+        self.file_name.to_string()
+    }
+
+    fn changed_on_disk(&self) -> bool {
+        self.seen
+            .lock()
+            .unwrap()
+            .is_some_and(|seen| seen != persist::modified(&self.path()))
+    }
+
+    fn load(&self) -> Result<Vec<Npc>, String> {
+        // A reload is how a broken file gets picked up again once fixed, and
+        // the file may no longer match what was last written
+        self.read_only.store(false, Ordering::Release);
+        *self.last_written.lock().unwrap() = None;
+
+        let path = self.path();
+        *self.seen.lock().unwrap() = Some(persist::modified(&path));
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", self.file_name))?;
+
+        let parsed = self
+            .parse(&contents)
+            .and_then(|(version, raw)| Ok((version, schema::read_npcs(version, raw)?)));
+        let (version, npcs) = match parsed {
+            Ok(p) => p,
+            Err(e) => {
+                self.read_only.store(true, Ordering::Release);
+                let label = format!("broken-{}", persist::timestamp());
+                let kept = match persist::preserve_copy(&path, &label) {
+                    Ok(copy) => format!(", a copy was saved to {}", copy.display()),
+                    Err(copy_err) => format!(" (backing it up also failed: {copy_err})"),
+                };
+                return Err(format!(
+                    "Failed to load {}: {e}. The file will not be written to until it is fixed and reloaded{kept}",
+                    self.file_name
+                ));
+            }
+        };
+
+        if version < SCHEMA_VERSION {
+            log::info!(
+                "Upgrading {} from schema version {version} to {SCHEMA_VERSION}",
+                self.file_name
+            );
+            if let Err(e) = persist::preserve_copy(&path, &format!("v{version}")) {
+                log::warn!("Failed to back up {} before upgrading: {e}", self.file_name);
+            }
+            self.save_all(&npcs)?;
+        }
+
+        Ok(npcs)
+    }
+
+    fn save_all(&self, npcs: &[Npc]) -> Result<(), String> {
+        if self.read_only.load(Ordering::Acquire) {
+            return Err(format!(
+                "Not saving NPCs: {} failed to load and is left untouched",
+                self.file_name
+            ));
+        }
+
+        let mut sorted = npcs.to_vec();
+        sorted.sort_by_key(|n| n.id);
+        let config = NpcConfig {
+            version: SCHEMA_VERSION,
+            npcs: &sorted,
+        };
+
+        let contents = match self.format {
+            Format::Toml => toml::to_string_pretty(&config).map_err(|e| e.to_string()),
+            Format::Json => serde_json::to_string_pretty(&config).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("Failed to serialize NPCs: {e}"))?;
+
+        let mut last_written = self.last_written.lock().unwrap();
+        if last_written.as_deref() == Some(contents.as_str()) {
+            return Ok(());
+        }
+
+        let path = self.path();
+        if self.changed_on_disk() && path.exists() {
+            let label = format!("overwritten-{}", persist::timestamp());
+            match persist::preserve_copy(&path, &label) {
+                Ok(copy) => log::warn!(
+                    "{} was changed on disk while NPCs were edited in game; the in-game edits were saved and the file's version was copied to {}",
+                    self.file_name,
+                    copy.display()
+                ),
+                Err(e) => log::warn!(
+                    "{} was changed on disk while NPCs were edited in game, and keeping a copy of it failed: {e}",
+                    self.file_name
+                ),
+            }
+        }
+
+        let backups = crate::config::config().backups;
+        persist::write_atomic(&path, &contents, backups)
+            .map_err(|e| format!("Failed to write {}: {e}", self.file_name))?;
+        *last_written = Some(contents);
+        *self.seen.lock().unwrap() = Some(persist::modified(&path));
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::Npc;
+
+mod file;
+mod schema;
+mod sqlite;
+
+pub use file::FileStorage;
+pub use sqlite::SqliteStorage;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    Toml,
+    Json,
+    Sqlite,
+}
+
+impl StorageKind {
+    pub const ALL: [StorageKind; 3] = [StorageKind::Toml, StorageKind::Json, StorageKind::Sqlite];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StorageKind::Toml => "toml",
+            StorageKind::Json => "json",
+            StorageKind::Sqlite => "sqlite",
+        }
+    }
+}
+
+impl fmt::Display for StorageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for StorageKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StorageKind::ALL
+            .into_iter()
+            .find(|k| k.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown storage backend '{s}' (expected toml, json or sqlite)"))
+    }
+}
+
+/// Where NPC definitions are persisted. Runtime fields (UUIDs, entity IDs) are
+/// never stored and are filled in by the registry after loading.
+pub trait NpcStorage: Send + Sync {
+    /// Human readable location, e.g. the file name, for log messages.
+    fn describe(&self) -> String;
+
+    fn load(&self) -> Result<Vec<Npc>, String>;
+
+    /// Replaces everything in the storage with `npcs`.
+    fn save_all(&self, npcs: &[Npc]) -> Result<(), String>;
+
+    /// Whether something else, e.g. a hand edit, changed the stored NPCs
+    /// since they were last loaded or written.
+    fn changed_on_disk(&self) -> bool;
+
+    /// Whether `save_changes` can write single NPCs. Backends that can't are
+    /// saved by rewriting every NPC with `save_all`.
+    fn writes_single_npcs(&self) -> bool {
+        false
+    }
+
+    /// Persists a batch of edits. Only called when `writes_single_npcs`.
+    fn save_changes(&self, changed: &[Npc], removed: &[u32]) -> Result<(), String> {
+        let _ = (changed, removed);
+        Err(format!(
+            "{} can only be rewritten as a whole",
+            self.describe()
+        ))
+    }
+}
+
+pub fn open(kind: StorageKind) -> Result<Arc<dyn NpcStorage>, String> {
+    Ok(match kind {
+        StorageKind::Toml => Arc::new(FileStorage::toml()),
+        StorageKind::Json => Arc::new(FileStorage::json()),
+        StorageKind::Sqlite => Arc::new(SqliteStorage::open()?),
+    })
+}
+
+/// Runs `f` on the blocking thread pool, so file and database I/O never
+/// stalls the tasks handling players.
+pub async fn run<T, F>(storage: &Arc<dyn NpcStorage>, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn NpcStorage) -> Result<T, String> + Send + 'static,
+{
+    let storage = Arc::clone(storage);
+    tokio::task::spawn_blocking(move || f(storage.as_ref()))
+        .await
+        .map_err(|e| format!("Storage task failed: {e}"))?
+}
@@ -0,0 +1,146 @@
+use serde_json::{Map, Value};
+
+use crate::npc::{Npc, DEFAULT_WORLD};
+
+/// Version of the NPC layout written by this build. Bump it together with a
+/// new entry in `MIGRATIONS` whenever a stored field is added without a
+/// default, renamed or restructured.
+pub const SCHEMA_VERSION: u32 = 4;
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades a single NPC from schema `n + 1` to `n + 2`.
+const MIGRATIONS: &[Migration] = &[v1_to_v2, v2_to_v3, v3_to_v4];
+
+/// Version 1 predates worlds; every NPC lived in the overworld.
+fn v1_to_v2(npc: &mut Map<String, Value>) {
+    if let Some(Value::Object(location)) = npc.get_mut("location") {
+        location
+            .entry("world")
+            .or_insert_with(|| Value::String(DEFAULT_WORLD.to_string()));
+    }
+}
+
+/// Version 3 adds unique keys. IDs are already unique, so derive from those.
+fn v2_to_v3(npc: &mut Map<String, Value>) {
+    if npc.contains_key("key") {
+        return;
+    }
+    if let Some(id) = npc.get("id").and_then(Value::as_u64) {
+        npc.insert("key".to_string(), Value::String(format!("npc_{id}")));
+    }
+}
+
+/// Version 4 separates the display name from the profile name sent to
+/// clients, which is now generated.
+fn v3_to_v4(npc: &mut Map<String, Value>) {
+    if let Some(name) = npc.remove("name") {
+        npc.entry("display_name").or_insert(name);
+    }
+}
+
+/// Upgrades raw NPC records stored at `version` to the current schema and
+/// deserializes them. Fails on anything it can't fully understand, including
+/// data written by a newer build, so callers never overwrite it.
+pub fn read_npcs(version: u32, raw: Vec<Value>) -> Result<Vec<Npc>, String> {
+    if version == 0 {
+        return Err("invalid schema version 0".to_string());
+    }
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "schema version {version} is newer than this build supports ({SCHEMA_VERSION})"
+        ));
+    }
+
+    let pending = &MIGRATIONS[(version - 1) as usize..];
+    raw.into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let Value::Object(mut npc) = value else {
+                return Err(format!("NPC entry {} is not a table", i + 1));
+            };
+            for migrate in pending {
+                migrate(&mut npc);
+            }
+            serde_json::from_value(Value::Object(npc))
+                .map_err(|e| format!("NPC entry {}: {e}", i + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn v1_npc() -> Value {
+        json!({
+            "id": 7,
+            "name": "Guide",
+            "location": { "x": 1.5, "y": 64.0, "z": -2.5, "yaw": 90.0, "pitch": 0.0 },
+            "skin": null,
+            "look_at_nearest": true,
+            "holograms": [{ "text": "Hello" }],
+            "server": null
+        })
+    }
+
+    #[test]
+    fn migrates_v1_to_current() {
+        let npcs = read_npcs(1, vec![v1_npc()]).unwrap();
+        let npc = &npcs[0];
+        assert_eq!(npc.id, 7);
+        assert_eq!(npc.location.world, DEFAULT_WORLD);
+        assert_eq!(npc.key, "npc_7");
+        assert_eq!(npc.display_name, "Guide");
+        assert!(npc.look_at_nearest);
+        assert_eq!(npc.holograms[0].text, "Hello");
+    }
+
+    #[test]
+    fn migrations_keep_existing_values() {
+        let mut npc = v1_npc();
+        npc["location"]["world"] = json!("minecraft:the_nether");
+        npc["key"] = json!("guide");
+        npc["display_name"] = json!("The Guide");
+
+        let npcs = read_npcs(1, vec![npc]).unwrap();
+        assert_eq!(npcs[0].location.world, "minecraft:the_nether");
+        assert_eq!(npcs[0].key, "guide");
+        assert_eq!(npcs[0].display_name, "The Guide");
+    }
+
+    #[test]
+    fn reads_current_version_unchanged() {
+        let mut npc = v1_npc();
+        let fields = npc.as_object_mut().unwrap();
+        fields.remove("name");
+        fields.insert("key".to_string(), json!("guide"));
+        fields.insert("display_name".to_string(), json!("Guide"));
+        npc["location"]["world"] = json!(DEFAULT_WORLD);
+
+        let npcs = read_npcs(SCHEMA_VERSION, vec![npc]).unwrap();
+        assert_eq!(npcs[0].key, "guide");
+        assert_eq!(npcs[0].display_name, "Guide");
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        assert!(read_npcs(0, vec![v1_npc()]).is_err());
+        assert!(read_npcs(SCHEMA_VERSION + 1, vec![v1_npc()]).is_err());
+    }
+
+    #[test]
+    fn rejects_entries_that_are_not_tables() {
+        let err = read_npcs(1, vec![v1_npc(), json!(3)]).unwrap_err();
+        assert_eq!(err, "NPC entry 2 is not a table");
+    }
+
+    #[test]
+    fn rejects_entries_missing_required_fields() {
+        let mut npc = v1_npc();
+        npc.as_object_mut().unwrap().remove("location");
+        assert!(read_npcs(1, vec![npc]).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use rusqlite::{params, Connection, Transaction};
+use serde_json::Value;
+
+use super::schema::{self, SCHEMA_VERSION};
+use super::NpcStorage;
+use crate::npc::Npc;
+use crate::persist;
+use crate::DATA_FOLDER;
+
+const DB_FILE: &str = "npcs.db";
+
+fn db_path() -> PathBuf {
+    DATA_FOLDER
+        .get()
+        .expect("Data folder not initialized")
+        .join(DB_FILE)
+}
+
+/// Modification times of the database and its write-ahead log, which is
+/// where other connections' writes land first.
+fn stamp() -> (Option<SystemTime>, Option<SystemTime>) {
+    let db = db_path();
+    let wal = db.with_file_name(format!("{DB_FILE}-wal"));
+    (persist::modified(&db), persist::modified(&wal))
+}
+
+/// Stores one row per NPC, so an edit only rewrites the rows that changed.
+/// The schema version lives in SQLite's `user_version` pragma.
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+    /// Set when stored rows couldn't be read, so they are never overwritten.
+    read_only: AtomicBool,
+    /// `stamp()` after the database was last loaded or written.
+    seen: Mutex<Option<(Option<SystemTime>, Option<SystemTime>)>>,
+}
+
+impl SqliteStorage {
+    pub fn open() -> Result<Self, String> {
+        let conn =
+            Connection::open(db_path()).map_err(|e| format!("Failed to open {DB_FILE}: {e}"))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS npcs (
+                 id   INTEGER PRIMARY KEY,
+                 data TEXT NOT NULL
+             );",
+        )
+        .map_err(|e| format!("Failed to initialize {DB_FILE}: {e}"))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            read_only: AtomicBool::new(false),
+            seen: Mutex::new(None),
+        })
+    }
+
+    fn read_raw(&self) -> Result<(u32, Vec<Value>), String> {
+        let conn = self.conn.lock().unwrap();
+        let version: u32 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to read schema version: {e}"))?;
+
+        let mut stmt = conn
+            .prepare("SELECT id, data FROM npcs ORDER BY id")
+            .map_err(|e| format!("Failed to query NPCs: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| format!("Failed to query NPCs: {e}"))?;
+
+        let mut raw = Vec::new();
+        for row in rows {
+            let (id, data) = row.map_err(|e| format!("Failed to read NPC row: {e}"))?;
+            let value = serde_json::from_str(&data)
+                .map_err(|e| format!("NPC {id} is not valid JSON: {e}"))?;
+            raw.push(value);
+        }
+
+        // Databases created before versioning have no marker
+        Ok((version.max(1), raw))
+    }
+
+    /// Copies the database next to itself, e.g. before upgrading it. Recent
+    /// writes may still be in the write-ahead log, so they are moved into
+    /// the main file first.
+    fn preserve_copy(&self, label: &str) -> std::io::Result<PathBuf> {
+        let checkpoint =
+            self.conn
+                .lock()
+                .unwrap()
+                .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+                    row.get::<_, i64>(0)
+                });
+        match checkpoint {
+            Ok(0) => {}
+            Ok(_) => log::warn!("{DB_FILE} is busy, its copy may miss the latest writes"),
+            Err(e) => log::warn!(
+                "Failed to checkpoint {DB_FILE}, its copy may miss the latest writes: {e}"
+            ),
+        }
+        let copy = persist::preserve_copy(&db_path(), label);
+        *self.seen.lock().unwrap() = Some(stamp());
+        copy
+    }
+
+    fn write<F>(&self, f: F) -> Result<(), String>
+    where
+        F: FnOnce(&Transaction<'_>) -> Result<(), String>,
+    {
+        if self.read_only.load(Ordering::Acquire) {
+            return Err(format!(
+                "Not saving NPCs: {DB_FILE} failed to load and is left untouched"
+            ));
+        }
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to write {DB_FILE}: {e}"))?;
+        f(&tx)?;
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(|e| format!("Failed to write {DB_FILE}: {e}"))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to write {DB_FILE}: {e}"))?;
+        *self.seen.lock().unwrap() = Some(stamp());
+        Ok(())
+    }
+}
+
+fn upsert(tx: &Transaction<'_>, npc: &Npc) -> Result<(), String> {
+    let data = serde_json::to_string(npc)
+        .map_err(|e| format!("Failed to serialize NPC {}: {e}", npc.id))?;
+    tx.execute(
+        "INSERT OR REPLACE INTO npcs (id, data) VALUES (?1, ?2)",
+        params![npc.id, data],
+    )
+    .map_err(|e| format!("Failed to write NPC {}: {e}", npc.id))?;
+    Ok(())
+}
+
+impl NpcStorage for SqliteStorage {
+    fn describe(&self) -> String {
+        DB_FILE.to_string()
+    }
+
+    fn changed_on_disk(&self) -> bool {
+        self.seen
+            .lock()
+            .unwrap()
+            .is_some_and(|seen| seen != stamp())
+    }
+
+    fn load(&self) -> Result<Vec<Npc>, String> {
+        self.read_only.store(false, Ordering::Release);
+        *self.seen.lock().unwrap() = Some(stamp());
+        let parsed = self
+            .read_raw()
+            .and_then(|(version, raw)| Ok((version, schema::read_npcs(version, raw)?)));
+        let (version, npcs) = match parsed {
+            Ok(p) => p,
+            Err(e) => {
+                self.read_only.store(true, Ordering::Release);
+                let label = format!("broken-{}", persist::timestamp());
+                let kept = match self.preserve_copy(&label) {
+                    Ok(copy) => format!(", a copy was saved to {}", copy.display()),
+                    Err(copy_err) => format!(" (backing it up also failed: {copy_err})"),
+                };
+                return Err(format!(
+                    "Failed to load {DB_FILE}: {e}. The database will not be written to until it is fixed and reloaded{kept}"
+                ));
+            }
+        };
+
+        if version < SCHEMA_VERSION && !npcs.is_empty() {
+            log::info!("Upgrading {DB_FILE} from schema version {version} to {SCHEMA_VERSION}");
+            if let Err(e) = self.preserve_copy(&format!("v{version}")) {
+                log::warn!("Failed to back up {DB_FILE} before upgrading: {e}");
+            }
+            self.save_all(&npcs)?;
+        }
+
+        Ok(npcs)
+    }
+
+    fn save_all(&self, npcs: &[Npc]) -> Result<(), String> {
+        self.write(|tx| {
+            tx.execute("DELETE FROM npcs", [])
+                .map_err(|e| format!("Failed to write {DB_FILE}: {e}"))?;
+            for npc in npcs {
+                upsert(tx, npc)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn writes_single_npcs(&self) -> bool {
+        true
+    }
+
+    fn save_changes(&self, changed: &[Npc], removed: &[u32]) -> Result<(), String> {
+        self.write(|tx| {
+            for npc in changed {
+                upsert(tx, npc)?;
+            }
+            for id in removed {
+                tx.execute("DELETE FROM npcs WHERE id = ?1", params![id])
+                    .map_err(|e| format!("Failed to delete NPC {id}: {e}"))?;
+            }
+            Ok(())
+        })
+    }
+}
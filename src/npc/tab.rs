@@ -0,0 +1,71 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabGameMode {
+    #[default]
+    Survival,
+    Creative,
+    Adventure,
+    /// Listed in gray at the bottom of the tab list.
+    Spectator,
+}
+
+impl TabGameMode {
+    pub fn protocol_id(self) -> i32 {
+        match self {
+            TabGameMode::Survival => 0,
+            TabGameMode::Creative => 1,
+            TabGameMode::Adventure => 2,
+            TabGameMode::Spectator => 3,
+        }
+    }
+}
+
+impl fmt::Display for TabGameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TabGameMode::Survival => "survival",
+            TabGameMode::Creative => "creative",
+            TabGameMode::Adventure => "adventure",
+            TabGameMode::Spectator => "spectator",
+        })
+    }
+}
+
+impl FromStr for TabGameMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "survival" => Ok(TabGameMode::Survival),
+            "creative" => Ok(TabGameMode::Creative),
+            "adventure" => Ok(TabGameMode::Adventure),
+            "spectator" => Ok(TabGameMode::Spectator),
+            _ => Err(format!(
+                "Unknown game mode '{s}' (expected survival, creative, adventure or spectator)"
+            )),
+        }
+    }
+}
+
+/// How a player NPC appears in the tab list. NPCs are unlisted by default.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NpcTab {
+    pub listed: bool,
+    /// Text shown in the list; the NPC's display name when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Ping in milliseconds, which decides the latency bars shown.
+    pub latency: i32,
+    pub game_mode: TabGameMode,
+    /// Higher values are listed first.
+    pub order: i32,
+    /// Entries in the same group are listed together, groups sorted by name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
@@ -0,0 +1,411 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use pumpkin::entity::player::Player;
+use pumpkin_util::math::vector3::Vector3;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::animation::NpcAnimation;
+use super::hologram::{despawn_holograms_for_player, spawn_holograms_for_player};
+use super::packets::{
+    despawn_npc_for_player, play_animation_for_player, spawn_npc_for_player,
+    teleport_npc_for_player,
+};
+use super::{player_world, Npc, NpcLocation};
+use crate::holo::Hologram;
+use crate::{CONTEXT, HOLOGRAMS, REGISTRY};
+
+fn in_view(location: &NpcLocation, world: &str, pos: &Vector3<f64>) -> bool {
+    if location.world != world {
+        return false;
+    }
+
+    let config = crate::config::config();
+    if let Some(radius) = config.view_chunk_radius {
+        let chunk = |v: f64| (v.floor() as i64) >> 4;
+        let dx = (chunk(location.x) - chunk(pos.x)).abs();
+        let dz = (chunk(location.z) - chunk(pos.z)).abs();
+        return dx.max(dz) <= i64::from(radius);
+    }
+
+    let dx = pos.x - location.x;
+    let dz = pos.z - location.z;
+    dx * dx + dz * dz <= config.view_distance * config.view_distance
+}
+
+fn online_players() -> Vec<Arc<Player>> {
+    CONTEXT
+        .get()
+        .map(|c| c.server.get_all_players())
+        .unwrap_or_default()
+}
+
+/// Keeps track of which NPCs and standalone holograms have been spawned on
+/// each player's client, so they can be sent when a player comes into range
+/// and removed when they leave.
+pub struct ViewTracker {
+    viewing: RwLock<HashMap<Uuid, HashSet<u32>>>,
+    /// Standalone holograms, which have their own IDs.
+    holograms: RwLock<HashMap<Uuid, HashSet<u32>>>,
+    /// NPCs each player is within the approach distance of, so approach
+    /// animations only play when a player comes close, not on every step.
+    nearby: RwLock<HashMap<Uuid, HashSet<u32>>>,
+    /// Players that changed world, with the key of the world they went to.
+    /// Their client drops entities sent while it is still loading that world,
+    /// so nothing is sent to them until it moves there.
+    respawning: RwLock<HashMap<Uuid, String>>,
+    /// World and block column each player's view was last worked out at.
+    /// Most moves stay within a block, and those are skipped.
+    columns: RwLock<HashMap<Uuid, (String, i64, i64)>>,
+}
+
+impl ViewTracker {
+    pub fn new() -> Self {
+        Self {
+            viewing: RwLock::new(HashMap::new()),
+            holograms: RwLock::new(HashMap::new()),
+            nearby: RwLock::new(HashMap::new()),
+            respawning: RwLock::new(HashMap::new()),
+            columns: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn is_viewing(&self, player_uuid: &Uuid, npc_id: u32) -> bool {
+        self.viewing
+            .read()
+            .await
+            .get(player_uuid)
+            .is_some_and(|ids| ids.contains(&npc_id))
+    }
+
+    /// Online players that currently have the NPC spawned.
+    pub async fn viewers(&self, npc_id: u32) -> Vec<Arc<Player>> {
+        let viewing = self.viewing.read().await;
+        online_players()
+            .into_iter()
+            .filter(|p| {
+                viewing
+                    .get(&p.gameprofile.id)
+                    .is_some_and(|ids| ids.contains(&npc_id))
+            })
+            .collect()
+    }
+
+    /// Spawns NPCs that are now in range of `pos` and despawns those that are
+    /// not. Nothing is worked out again until the player leaves the block
+    /// column they were last updated in.
+    pub async fn update_player(&self, player: &Arc<Player>, pos: &Vector3<f64>) {
+        let Some(registry) = REGISTRY.get() else {
+            return;
+        };
+        if self.is_respawning(&player.gameprofile.id).await {
+            return;
+        }
+
+        let world = player_world(player);
+        let column = (world, pos.x.floor() as i64, pos.z.floor() as i64);
+        {
+            let mut columns = self.columns.write().await;
+            if columns.get(&player.gameprofile.id) == Some(&column) {
+                return;
+            }
+            columns.insert(player.gameprofile.id, column.clone());
+        }
+        let world = column.0;
+
+        let mut to_spawn = Vec::new();
+        let mut to_despawn = Vec::new();
+        {
+            let mut viewing = self.viewing.write().await;
+            let ids = viewing.entry(player.gameprofile.id).or_default();
+            registry
+                .for_each(|npc| {
+                    let visible = in_view(&npc.location, &world, pos);
+                    if visible && ids.insert(npc.id) {
+                        to_spawn.push(npc.clone());
+                    } else if !visible && ids.remove(&npc.id) {
+                        to_despawn.push(npc.clone());
+                    }
+                })
+                .await;
+        }
+
+        for npc in &to_despawn {
+            despawn_npc_for_player(npc, player).await;
+        }
+        for npc in &to_spawn {
+            spawn_npc_for_player(npc, player).await;
+        }
+
+        self.update_player_holograms(player, &world, pos).await;
+    }
+
+    async fn update_player_holograms(&self, player: &Arc<Player>, world: &str, pos: &Vector3<f64>) {
+        let Some(registry) = HOLOGRAMS.get() else {
+            return;
+        };
+
+        let mut to_spawn = Vec::new();
+        let mut to_despawn = Vec::new();
+        {
+            let mut viewing = self.holograms.write().await;
+            let ids = viewing.entry(player.gameprofile.id).or_default();
+            registry
+                .for_each(|hologram| {
+                    let visible = in_view(&hologram.location, world, pos);
+                    if visible && ids.insert(hologram.id) {
+                        to_spawn.push(hologram.clone());
+                    } else if !visible && ids.remove(&hologram.id) {
+                        to_despawn.push(hologram.clone());
+                    }
+                })
+                .await;
+        }
+
+        for hologram in &to_despawn {
+            despawn_holograms_for_player(hologram, player).await;
+        }
+        for hologram in &to_spawn {
+            spawn_holograms_for_player(hologram, player).await;
+        }
+    }
+
+    /// Despawns everything the player was viewing and forgets it, e.g. before
+    /// they move to another world.
+    pub async fn clear_player(&self, player: &Arc<Player>) {
+        let Some(registry) = REGISTRY.get() else {
+            return;
+        };
+        self.nearby.write().await.remove(&player.gameprofile.id);
+        self.columns.write().await.remove(&player.gameprofile.id);
+        let ids = self.viewing.write().await.remove(&player.gameprofile.id);
+        for id in ids.unwrap_or_default() {
+            if let Some(npc) = registry.get(id).await {
+                despawn_npc_for_player(&npc, player).await;
+            }
+        }
+
+        let ids = self.holograms.write().await.remove(&player.gameprofile.id);
+        if let Some(holograms) = HOLOGRAMS.get() {
+            for id in ids.unwrap_or_default() {
+                if let Some(hologram) = holograms.get(id).await {
+                    despawn_holograms_for_player(&hologram, player).await;
+                }
+            }
+        }
+    }
+
+    pub async fn remove_player(&self, player_uuid: &Uuid) {
+        self.viewing.write().await.remove(player_uuid);
+        self.holograms.write().await.remove(player_uuid);
+        self.nearby.write().await.remove(player_uuid);
+        self.respawning.write().await.remove(player_uuid);
+        self.columns.write().await.remove(player_uuid);
+    }
+
+    /// Despawns everything for a player moving to `world` and holds back
+    /// everything there until `confirm_world` sees their client in it.
+    pub async fn start_respawn(&self, player: &Arc<Player>, world: String) {
+        self.respawning
+            .write()
+            .await
+            .insert(player.gameprofile.id, world);
+        self.clear_player(player).await;
+    }
+
+    /// Called when the player's client moves. The client only sends its
+    /// position once it has loaded the world it respawned in, and keeps
+    /// sending it at least once a second while standing still, so the first
+    /// move in the new world is when it can be sent that world's NPCs.
+    pub async fn confirm_world(&self, player: &Arc<Player>) {
+        let uuid = player.gameprofile.id;
+        if !self.is_respawning(&uuid).await {
+            return;
+        }
+        let world = player_world(player);
+        let mut respawning = self.respawning.write().await;
+        if respawning.get(&uuid) == Some(&world) {
+            respawning.remove(&uuid);
+        }
+    }
+
+    async fn is_respawning(&self, player_uuid: &Uuid) -> bool {
+        self.respawning.read().await.contains_key(player_uuid)
+    }
+
+    /// Plays an animation on the NPC for everyone viewing it.
+    pub async fn animate(&self, npc: &Npc, animation: NpcAnimation) {
+        for player in self.viewers(npc.id).await {
+            play_animation_for_player(npc, animation, &player).await;
+        }
+    }
+
+    /// Plays the approach animation of every NPC the player just came close to.
+    pub async fn check_approach(&self, player: &Arc<Player>, pos: &Vector3<f64>) {
+        let Some(registry) = REGISTRY.get() else {
+            return;
+        };
+
+        let viewing: Vec<u32> = self
+            .viewing
+            .read()
+            .await
+            .get(&player.gameprofile.id)
+            .map(|ids| ids.iter().copied().collect())
+            .unwrap_or_default();
+        let mut npcs = Vec::with_capacity(viewing.len());
+        for id in viewing {
+            if let Some(npc) = registry.get(id).await {
+                npcs.push(npc);
+            }
+        }
+
+        let mut approached = Vec::new();
+        {
+            let mut nearby = self.nearby.write().await;
+            let ids = nearby.entry(player.gameprofile.id).or_default();
+            ids.retain(|id| npcs.iter().any(|n| n.id == *id));
+            for npc in npcs {
+                let Some(animation) = npc.animations.on_approach else {
+                    ids.remove(&npc.id);
+                    continue;
+                };
+
+                let dx = pos.x - npc.location.x;
+                let dy = pos.y - npc.location.y;
+                let dz = pos.z - npc.location.z;
+                let distance = npc.animations.approach_distance;
+                let close = dx * dx + dy * dy + dz * dz <= distance * distance;
+                if !close {
+                    ids.remove(&npc.id);
+                } else if ids.insert(npc.id) {
+                    approached.push((npc, animation));
+                }
+            }
+        }
+
+        for (npc, animation) in &approached {
+            self.animate(npc, *animation).await;
+        }
+    }
+
+    /// Re-evaluates a single NPC for every online player, spawning it for
+    /// those now in range and despawning it for those that are not.
+    pub async fn refresh_npc(&self, npc: &Npc) {
+        self.sync_npc(npc, false).await;
+    }
+
+    /// Like `refresh_npc`, but also moves the NPC for players that could
+    /// already see it. Used after its location changed.
+    pub async fn relocate_npc(&self, npc: &Npc) {
+        self.sync_npc(npc, true).await;
+    }
+
+    async fn sync_npc(&self, npc: &Npc, teleport_existing: bool) {
+        for player in online_players() {
+            if self.is_respawning(&player.gameprofile.id).await {
+                continue;
+            }
+            let world = player_world(&player);
+            let pos = player.living_entity.entity.pos.load();
+            let visible = in_view(&npc.location, &world, &pos);
+
+            let changed = {
+                let mut viewing = self.viewing.write().await;
+                let ids = viewing.entry(player.gameprofile.id).or_default();
+                if visible {
+                    ids.insert(npc.id)
+                } else {
+                    ids.remove(&npc.id)
+                }
+            };
+
+            if !changed {
+                if visible && teleport_existing {
+                    teleport_npc_for_player(npc, &player).await;
+                }
+                continue;
+            }
+            if visible {
+                spawn_npc_for_player(npc, &player).await;
+            } else {
+                despawn_npc_for_player(npc, &player).await;
+            }
+        }
+    }
+
+    /// Despawns a removed NPC for everyone viewing it.
+    pub async fn forget_npc(&self, npc: &Npc) {
+        let viewers = self.viewers(npc.id).await;
+        {
+            let mut viewing = self.viewing.write().await;
+            for ids in viewing.values_mut() {
+                ids.remove(&npc.id);
+            }
+        }
+        for player in &viewers {
+            despawn_npc_for_player(npc, player).await;
+        }
+    }
+
+    /// Online players that currently have the standalone hologram spawned.
+    pub async fn hologram_viewers(&self, hologram_id: u32) -> Vec<Arc<Player>> {
+        let viewing = self.holograms.read().await;
+        online_players()
+            .into_iter()
+            .filter(|p| {
+                viewing
+                    .get(&p.gameprofile.id)
+                    .is_some_and(|ids| ids.contains(&hologram_id))
+            })
+            .collect()
+    }
+
+    /// Spawns a standalone hologram for players now in range and despawns it
+    /// for those that are not.
+    pub async fn refresh_hologram(&self, hologram: &Hologram) {
+        for player in online_players() {
+            if self.is_respawning(&player.gameprofile.id).await {
+                continue;
+            }
+            let world = player_world(&player);
+            let pos = player.living_entity.entity.pos.load();
+            let visible = in_view(&hologram.location, &world, &pos);
+
+            let changed = {
+                let mut viewing = self.holograms.write().await;
+                let ids = viewing.entry(player.gameprofile.id).or_default();
+                if visible {
+                    ids.insert(hologram.id)
+                } else {
+                    ids.remove(&hologram.id)
+                }
+            };
+
+            if !changed {
+                continue;
+            }
+            if visible {
+                spawn_holograms_for_player(hologram, &player).await;
+            } else {
+                despawn_holograms_for_player(hologram, &player).await;
+            }
+        }
+    }
+
+    /// Despawns a removed or replaced standalone hologram for everyone
+    /// viewing it.
+    pub async fn forget_hologram(&self, hologram: &Hologram) {
+        let viewers = self.hologram_viewers(hologram.id).await;
+        {
+            let mut viewing = self.holograms.write().await;
+            for ids in viewing.values_mut() {
+                ids.remove(&hologram.id);
+            }
+        }
+        for player in &viewers {
+            despawn_holograms_for_player(hologram, player).await;
+        }
+    }
+}
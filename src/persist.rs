@@ -0,0 +1,72 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    with_suffix(path, &format!(".bak.{n}"))
+}
+
+/// Shifts `file.bak.1` .. `file.bak.{keep-1}` up by one, dropping the oldest,
+/// and copies the current file to `file.bak.1`.
+fn rotate_backups(path: &Path, keep: usize) -> std::io::Result<()> {
+    let oldest = backup_path(path, keep);
+    if oldest.exists() {
+        std::fs::remove_file(&oldest)?;
+    }
+    for n in (1..keep).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            std::fs::rename(&from, backup_path(path, n + 1))?;
+        }
+    }
+    std::fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// Writes `contents` to a temp file next to `path` and renames it into place,
+/// so a crash mid-write never leaves a truncated file behind. The previous
+/// version is kept in up to `backups` rotating `.bak.N` files.
+pub fn write_atomic(path: &Path, contents: &str, backups: usize) -> std::io::Result<()> {
+    let tmp = with_suffix(path, ".tmp");
+    {
+        let mut file = File::create(&tmp)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+    }
+
+    if backups > 0 && path.exists() {
+        if let Err(e) = rotate_backups(path, backups) {
+            log::warn!("Failed to rotate backups of {}: {e}", path.display());
+        }
+    }
+
+    std::fs::rename(&tmp, path)
+}
+
+/// Copies `path` to `path.{label}`, e.g. before migrating or when the file
+/// can't be parsed, and returns the copy's location.
+pub fn preserve_copy(path: &Path, label: &str) -> std::io::Result<PathBuf> {
+    let copy = with_suffix(path, &format!(".{label}"));
+    std::fs::copy(path, &copy)?;
+    Ok(copy)
+}
+
+/// When `path` was last modified, or `None` if it doesn't exist.
+pub fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Seconds since the Unix epoch, for naming preserved copies.
+pub fn timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration};
+
+use crate::{DATA_FOLDER, HOLOGRAMS, REGISTRY, TRACKER};
+
+/// Files that trigger a reload when changed on disk.
+const WATCHED_FILES: [&str; 4] = ["npcs.toml", "npcs.json", "servers.toml", "holograms.toml"];
+
+/// Editors often save in several steps, so wait for changes to settle.
+const WATCH_SETTLE: Duration = Duration::from_millis(500);
+
+/// Re-reads NPCs, holograms and servers from disk and applies the
+/// differences. Only NPCs and holograms that were added, removed or changed
+/// are respawned. `force` drops in-game NPC edits that conflict with changes
+/// on disk, see `NpcRegistry::reload`. Returns a summary.
+pub async fn reload_all(force: bool) -> Result<String, String> {
+    let registry = REGISTRY.get().ok_or("NPC registry not initialized")?;
+    let tracker = TRACKER.get().ok_or("NPC tracker not initialized")?;
+
+    let npcs = registry.reload(force).await?;
+
+    for npc in &npcs.removed {
+        tracker.forget_npc(npc).await;
+    }
+    for (old, new) in &npcs.changed {
+        tracker.forget_npc(old).await;
+        tracker.refresh_npc(new).await;
+    }
+    for npc in &npcs.added {
+        tracker.refresh_npc(npc).await;
+    }
+
+    let holograms = HOLOGRAMS
+        .get()
+        .ok_or("Hologram registry not initialized")?
+        .reload()
+        .await?;
+
+    for hologram in &holograms.removed {
+        tracker.forget_hologram(hologram).await;
+    }
+    for (old, new) in &holograms.changed {
+        tracker.forget_hologram(old).await;
+        tracker.refresh_hologram(new).await;
+    }
+    for hologram in &holograms.added {
+        tracker.refresh_hologram(hologram).await;
+    }
+
+    let servers = crate::status::reload_servers()?;
+
+    Ok(format!(
+        "Reloaded NPCs ({} added, {} removed, {} changed), holograms ({} added, {} removed, {} changed) and servers ({} added, {} removed, {} changed)",
+        npcs.added.len(),
+        npcs.removed.len(),
+        npcs.changed.len(),
+        holograms.added.len(),
+        holograms.removed.len(),
+        holograms.changed.len(),
+        servers.added.len(),
+        servers.removed.len(),
+        servers.changed.len()
+    ))
+}
+
+fn is_watched(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| WATCHED_FILES.contains(&n))
+}
+
+/// Watches the data folder and reloads when one of the config files changes.
+pub fn start_watcher() {
+    let folder = DATA_FOLDER.get().expect("Data folder not initialized");
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            if !event.kind.is_access() && event.paths.iter().any(|p| is_watched(p)) {
+                let _ = tx.send(());
+            }
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(w) => w,
+        Err(e) => {
+            log::error!("Failed to create file watcher: {e}");
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(folder, RecursiveMode::NonRecursive) {
+        log::error!("Failed to watch {}: {e}", folder.display());
+        return;
+    }
+
+    log::info!("Watching config files for changes");
+
+    tokio::spawn(async move {
+        // Dropping the watcher stops it, so it lives as long as this task
+        let _watcher = watcher;
+        while rx.recv().await.is_some() {
+            sleep(WATCH_SETTLE).await;
+            while rx.try_recv().is_ok() {}
+
+            // Our own writes land here too. If edits are still queued the
+            // file is about to be rewritten anyway, so wait for that write.
+            // If the file was edited by hand meanwhile, the write keeps a
+            // copy of it.
+            if REGISTRY.get().is_some_and(|r| r.has_pending_changes()) {
+                continue;
+            }
+
+            match reload_all(false).await {
+                Ok(summary) => log::info!("{summary}"),
+                Err(e) => log::error!("Reload failed: {e}"),
+            }
+        }
+    });
+}
@@ -2,21 +2,14 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::{Arc, OnceLock};
 
-use bytes::BufMut;
-use pumpkin::entity::player::Player;
-use pumpkin::net::ClientPlatform;
-use pumpkin_data::meta_data_type::MetaDataType;
-use pumpkin_data::tracked_data::TrackedData;
-use pumpkin_protocol::codec::var_int::VarInt;
-use pumpkin_protocol::java::client::play::{CSetEntityMetadata, Metadata};
-use pumpkin_util::text::TextComponent;
 use serde::Deserialize;
 use std::sync::RwLock;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio::time::{timeout, Duration};
 
-use crate::{CONTEXT, DATA_FOLDER, REGISTRY};
+use crate::npc::hologram::{update_text_for_player, HologramAnchor};
+use crate::{DATA_FOLDER, HOLOGRAMS, REGISTRY, TRACKER};
 
 const SERVERS_FILE: &str = "servers.toml";
 const PING_TIMEOUT: Duration = Duration::from_secs(2);
@@ -176,13 +169,13 @@ pub fn resolve_placeholders(text: &str, server_name: &str) -> String {
 
     let (status_str, online_str, max_str) = if status.online {
         (
-            "\u{00A7}aOnline".to_string(),
+            "<green>Online</green>".to_string(),
             status.players_online.to_string(),
             status.players_max.to_string(),
         )
     } else {
         (
-            "\u{00A7}cOffline".to_string(),
+            "<red>Offline</red>".to_string(),
             "0".to_string(),
             "0".to_string(),
         )
@@ -197,39 +190,6 @@ pub fn has_placeholders(text: &str) -> bool {
     text.contains("{status}") || text.contains("{online}") || text.contains("{max}")
 }
 
-async fn update_hologram_text(entity_id: i32, text: &str, player: &Arc<Player>) {
-    let ClientPlatform::Java(java) = &player.client else {
-        return;
-    };
-
-    let version = java.version.load();
-    let mut meta_buf = Vec::new();
-
-    let name = Metadata::new(
-        TrackedData::DATA_CUSTOM_NAME,
-        MetaDataType::OptionalTextComponent,
-        Some(TextComponent::text(text.to_string())),
-    );
-    if let Err(e) = name.write(&mut meta_buf, &version) {
-        log::error!("Failed to write hologram name update: {e:?}");
-        return;
-    }
-
-    meta_buf.put_u8(0xFF);
-
-    let packet = CSetEntityMetadata::new(VarInt(entity_id), meta_buf.into_boxed_slice());
-
-    let mut buf = Vec::new();
-    match java.write_packet(&packet, &mut buf) {
-        Ok(()) => {
-            java.enqueue_packet_data(buf.into()).await;
-        }
-        Err(e) => {
-            log::error!("Failed to serialize hologram update packet: {e:?}");
-        }
-    }
-}
-
 pub fn add_server(name: String, addr: SocketAddr) {
     let servers = servers();
 
@@ -333,40 +293,33 @@ pub fn start_status_task() {
     });
 }
 
-async fn push_hologram_updates() {
-    let context = match CONTEXT.get() {
-        Some(c) => c,
-        None => return,
-    };
+/// Whether a hologram shows status placeholders that need refreshing.
+fn needs_refresh(hologram: &impl HologramAnchor) -> bool {
+    hologram.server().is_some()
+        && hologram
+            .lines()
+            .iter()
+            .any(|line| has_placeholders(&line.text))
+}
 
-    let registry = match REGISTRY.get() {
-        Some(r) => r,
-        None => return,
+async fn push_hologram_updates() {
+    let (registry, tracker) = match (REGISTRY.get(), TRACKER.get()) {
+        (Some(r), Some(t)) => (r, t),
+        _ => return,
     };
 
-    let npcs = registry.all().await;
-    let players = context.server.get_all_players();
-
-    if players.is_empty() {
-        return;
+    for npc in registry.all().await.iter().filter(|n| needs_refresh(*n)) {
+        for player in &tracker.viewers(npc.id).await {
+            update_text_for_player(npc, player).await;
+        }
     }
 
-    for npc in &npcs {
-        let server_name = match &npc.server {
-            Some(s) => s,
-            None => continue,
-        };
-
-        for line in &npc.holograms {
-            if !has_placeholders(&line.text) {
-                continue;
-            }
-
-            let resolved = resolve_placeholders(&line.text, server_name);
-
-            for player in &players {
-                update_hologram_text(line.entity_id, &resolved, player).await;
-            }
+    let Some(holograms) = HOLOGRAMS.get() else {
+        return;
+    };
+    for hologram in holograms.all().await.iter().filter(|h| needs_refresh(*h)) {
+        for player in &tracker.hologram_viewers(hologram.id).await {
+            update_text_for_player(hologram, player).await;
         }
     }
 }
@@ -390,29 +343,26 @@ fn save_servers_config(servers: &HashMap<String, SocketAddr>) {
 }
 
 fn load_servers_config() -> HashMap<String, SocketAddr> {
+    read_servers_config().unwrap_or_else(|e| {
+        log::error!("{e}");
+        HashMap::new()
+    })
+}
+
+fn read_servers_config() -> Result<HashMap<String, SocketAddr>, String> {
     let path = DATA_FOLDER
         .get()
         .expect("Data folder not initialized")
         .join(SERVERS_FILE);
     if !path.exists() {
-        return HashMap::new();
+        return Ok(HashMap::new());
     }
 
-    let contents = match std::fs::read_to_string(&path) {
-        Ok(c) => c,
-        Err(e) => {
-            log::error!("Failed to read {SERVERS_FILE}: {e}");
-            return HashMap::new();
-        }
-    };
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {SERVERS_FILE}: {e}"))?;
 
-    let config: ServersConfig = match toml::from_str(&contents) {
-        Ok(c) => c,
-        Err(e) => {
-            log::error!("Failed to parse {SERVERS_FILE}: {e}");
-            return HashMap::new();
-        }
-    };
+    let config: ServersConfig =
+        toml::from_str(&contents).map_err(|e| format!("Failed to parse {SERVERS_FILE}: {e}"))?;
 
     let mut result = HashMap::new();
     for (name, entry) in config.servers {
@@ -425,5 +375,49 @@ fn load_servers_config() -> HashMap<String, SocketAddr> {
             }
         }
     }
-    result
+    Ok(result)
+}
+
+/// Server names that were added, removed or got a new address on reload.
+#[derive(Default)]
+pub struct ServersDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+/// Re-reads `servers.toml` into the live server list. The polling task picks
+/// the new list up on its next round. On failure the current list is kept.
+pub fn reload_servers() -> Result<ServersDiff, String> {
+    let loaded = read_servers_config()?;
+    let servers = servers();
+
+    let mut diff = ServersDiff::default();
+    {
+        let mut map = servers.write().unwrap();
+        for (name, addr) in &loaded {
+            match map.get(name) {
+                None => diff.added.push(name.clone()),
+                Some(old) if old != addr => diff.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = map
+            .keys()
+            .filter(|name| !loaded.contains_key(*name))
+            .cloned()
+            .collect();
+        *map = loaded;
+    }
+
+    // Don't keep showing stale status for servers that are gone or moved
+    if let Some(status_map) = STATUS.get() {
+        let mut statuses = status_map.write().unwrap();
+        for name in diff.removed.iter().chain(&diff.changed) {
+            statuses.remove(name);
+        }
+    }
+
+    start_status_task();
+    Ok(diff)
 }
@@ -0,0 +1,481 @@
+//! Markup for text shown to players, such as holograms and nametags.
+//!
+//! Tags like `<red>`, `<bold>`, `<#ff8800>`, `<gradient:#f00:#00f>` and
+//! `<rainbow>` style the text after them until they are closed with `</red>`
+//! or `</>`. Legacy `&` and `§` codes keep working. Unknown tags are left as
+//! text and `\<` writes a literal `<`.
+
+use pumpkin_util::text::color::RGBColor;
+use pumpkin_util::text::TextComponent;
+
+use crate::npc::color::NamedColor;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Decorations {
+    bold: bool,
+    italic: bool,
+    underlined: bool,
+    strikethrough: bool,
+    obfuscated: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Style {
+    color: Option<u32>,
+    decorations: Decorations,
+    /// Index into `Parser::fills` for text colored per character. Takes
+    /// precedence over `color`.
+    fill: Option<usize>,
+}
+
+enum Fill {
+    Gradient(Vec<u32>),
+    Rainbow,
+}
+
+impl Fill {
+    /// Color of character `index` out of the `len` characters the fill spans.
+    fn color_at(&self, index: usize, len: usize) -> u32 {
+        match self {
+            Fill::Gradient(stops) if stops.len() == 1 => stops[0],
+            Fill::Gradient(stops) => {
+                let t = if len <= 1 {
+                    0.0
+                } else {
+                    index as f32 / (len - 1) as f32
+                };
+                let scaled = t * (stops.len() - 1) as f32;
+                let i = (scaled as usize).min(stops.len() - 2);
+                lerp(stops[i], stops[i + 1], scaled - i as f32)
+            }
+            Fill::Rainbow => hue(index as f32 / len.max(1) as f32),
+        }
+    }
+}
+
+pub(crate) fn lerp(from: u32, to: u32, t: f32) -> u32 {
+    [16, 8, 0].into_iter().fold(0, |rgb, shift| {
+        let a = ((from >> shift) & 0xFF) as f32;
+        let b = ((to >> shift) & 0xFF) as f32;
+        rgb | (((a + (b - a) * t).round() as u32) << shift)
+    })
+}
+
+/// Fully saturated color at `h` (0 to 1) around the color wheel.
+fn hue(h: f32) -> u32 {
+    let h6 = h.fract() * 6.0;
+    let x = 1.0 - (h6 % 2.0 - 1.0).abs();
+    let (r, g, b) = match h6 as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    let channel = |v: f32| (v * 255.0).round() as u32;
+    (channel(r) << 16) | (channel(g) << 8) | channel(b)
+}
+
+/// `#RRGGBB` or one of the sixteen named colors.
+pub(crate) fn parse_color(s: &str) -> Option<u32> {
+    match s.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 => u32::from_str_radix(hex, 16).ok(),
+        Some(_) => None,
+        None => s.parse::<NamedColor>().ok().map(NamedColor::rgb),
+    }
+}
+
+struct Span {
+    style: Style,
+    text: String,
+}
+
+#[derive(Default)]
+struct Parser {
+    spans: Vec<Span>,
+    fills: Vec<Fill>,
+    /// Open tags with the style that was active before each of them.
+    open_tags: Vec<(String, Style)>,
+    style: Style,
+}
+
+impl Parser {
+    fn push(&mut self, c: char) {
+        match self.spans.last_mut() {
+            Some(span) if span.style == self.style => span.text.push(c),
+            _ => self.spans.push(Span {
+                style: self.style.clone(),
+                text: c.to_string(),
+            }),
+        }
+    }
+
+    fn open(&mut self, name: &str, style: Style) {
+        let previous = std::mem::replace(&mut self.style, style);
+        self.open_tags.push((name.to_string(), previous));
+    }
+
+    fn close(&mut self, name: &str) -> bool {
+        let position = if name.is_empty() {
+            self.open_tags.len().checked_sub(1)
+        } else {
+            self.open_tags.iter().rposition(|(open, _)| open == name)
+        };
+        let Some(position) = position else {
+            return name.is_empty();
+        };
+        self.style = self.open_tags[position].1.clone();
+        self.open_tags.truncate(position);
+        true
+    }
+
+    /// Applies the tag between `<` and `>`. Returns false for unknown tags,
+    /// which are then kept as text.
+    fn tag(&mut self, tag: &str) -> bool {
+        let tag = tag.to_ascii_lowercase();
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.split(':').next().unwrap_or_default();
+            return self.close(name);
+        }
+
+        let (name, args) = match tag.split_once(':') {
+            Some((name, args)) => (name, Some(args)),
+            None => (tag.as_str(), None),
+        };
+        let mut style = self.style.clone();
+        match (name, args) {
+            ("bold" | "b", None) => style.decorations.bold = true,
+            ("italic" | "i" | "em", None) => style.decorations.italic = true,
+            ("underlined" | "u", None) => style.decorations.underlined = true,
+            ("strikethrough" | "st", None) => style.decorations.strikethrough = true,
+            ("obfuscated" | "obf", None) => style.decorations.obfuscated = true,
+            ("reset", None) => {
+                self.open_tags.clear();
+                self.style = Style::default();
+                return true;
+            }
+            ("color" | "colour" | "c", Some(color)) => {
+                let Some(color) = parse_color(color) else {
+                    return false;
+                };
+                style.color = Some(color);
+                style.fill = None;
+            }
+            ("gradient", args) => {
+                let stops: Option<Vec<u32>> = match args {
+                    Some(args) => args.split(':').map(parse_color).collect(),
+                    None => Some(vec![0xFFFFFF, 0x000000]),
+                };
+                let Some(stops) = stops else {
+                    return false;
+                };
+                style.fill = Some(self.fills.len());
+                self.fills.push(Fill::Gradient(stops));
+            }
+            ("rainbow", None) => {
+                style.fill = Some(self.fills.len());
+                self.fills.push(Fill::Rainbow);
+            }
+            (color, None) => {
+                let Some(color) = parse_color(color) else {
+                    return false;
+                };
+                style.color = Some(color);
+                style.fill = None;
+            }
+            _ => return false,
+        }
+        self.open(name, style);
+        true
+    }
+
+    /// Applies a legacy formatting code. Like in vanilla, a color clears the
+    /// decorations before it.
+    fn legacy(&mut self, code: char) -> bool {
+        let code = code.to_ascii_lowercase();
+        if let Some(color) = NamedColor::from_legacy_code(code) {
+            self.style = Style {
+                color: Some(color.rgb()),
+                ..Style::default()
+            };
+            return true;
+        }
+        let decorations = &mut self.style.decorations;
+        match code {
+            'k' => decorations.obfuscated = true,
+            'l' => decorations.bold = true,
+            'm' => decorations.strikethrough = true,
+            'n' => decorations.underlined = true,
+            'o' => decorations.italic = true,
+            'r' => self.style = Style::default(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Applies the tag or code starting with `c`, followed by `after`.
+    /// Returns how much of `after` it takes up, or `None` if `c` is text.
+    fn markup(&mut self, c: char, after: &str) -> Option<usize> {
+        match c {
+            '<' => {
+                let end = after.find('>')?;
+                self.tag(&after[..end]).then_some(end + 1)
+            }
+            '&' | '§' => {
+                // `&#RRGGBB` sets a hex color.
+                if let Some(color) = after
+                    .get(..7)
+                    .filter(|hex| hex.starts_with('#'))
+                    .and_then(parse_color)
+                {
+                    self.style = Style {
+                        color: Some(color),
+                        ..Style::default()
+                    };
+                    return Some(7);
+                }
+                let code = after.chars().next()?;
+                self.legacy(code).then_some(code.len_utf8())
+            }
+            _ => None,
+        }
+    }
+
+    fn finish(self) -> TextComponent {
+        match self.spans.as_slice() {
+            [] => return TextComponent::text(String::new()),
+            [span] if span.style == Style::default() => {
+                return TextComponent::text(span.text.clone());
+            }
+            _ => {}
+        }
+
+        let mut lengths = vec![0; self.fills.len()];
+        for span in &self.spans {
+            if let Some(fill) = span.style.fill {
+                lengths[fill] += span.text.chars().count();
+            }
+        }
+
+        let mut positions = vec![0; self.fills.len()];
+        let mut root = TextComponent::text(String::new());
+        for span in self.spans {
+            let decorations = span.style.decorations;
+            let Some(fill) = span.style.fill else {
+                root = root.add_child(styled(span.text, span.style.color, decorations));
+                continue;
+            };
+            for c in span.text.chars() {
+                let color = self.fills[fill].color_at(positions[fill], lengths[fill]);
+                positions[fill] += 1;
+                root = root.add_child(styled(c.to_string(), Some(color), decorations));
+            }
+        }
+        root
+    }
+}
+
+fn styled(text: String, color: Option<u32>, decorations: Decorations) -> TextComponent {
+    let mut component = TextComponent::text(text);
+    if let Some(rgb) = color {
+        component = component.color_rgb(RGBColor::new(
+            (rgb >> 16) as u8,
+            (rgb >> 8) as u8,
+            rgb as u8,
+        ));
+    }
+    if decorations.bold {
+        component = component.bold();
+    }
+    if decorations.italic {
+        component = component.italic();
+    }
+    if decorations.underlined {
+        component = component.underlined();
+    }
+    if decorations.strikethrough {
+        component = component.strikethrough();
+    }
+    if decorations.obfuscated {
+        component = component.obfuscated();
+    }
+    component
+}
+
+/// Walks `input`, applying its tags and codes to `parser`. `visit` gets each
+/// piece of the source, along with the character it shows unless it's markup.
+fn scan<'a>(
+    input: &'a str,
+    parser: &mut Parser,
+    mut visit: impl FnMut(&mut Parser, &'a str, Option<char>),
+) {
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+        if c == '\\' && after.starts_with('<') {
+            visit(parser, &rest[..2], Some('<'));
+            rest = &after[1..];
+        } else if let Some(len) = parser.markup(c, after) {
+            visit(parser, &rest[..c.len_utf8() + len], None);
+            rest = &after[len..];
+        } else {
+            visit(parser, &rest[..c.len_utf8()], Some(c));
+            rest = after;
+        }
+    }
+}
+
+/// Parses markup and legacy color codes into a text component.
+pub fn parse(input: &str) -> TextComponent {
+    let mut parser = Parser::default();
+    scan(input, &mut parser, |parser, _, c| {
+        if let Some(c) = c {
+            parser.push(c);
+        }
+    });
+    parser.finish()
+}
+
+/// Number of characters `input` shows, not counting markup.
+pub fn visible_len(input: &str) -> usize {
+    let mut len = 0;
+    scan(input, &mut Parser::default(), |_, _, c| {
+        len += usize::from(c.is_some());
+    });
+    len
+}
+
+/// Cuts the `len` shown characters from `start` on out of `input`. All the
+/// markup is kept, so the characters keep their style.
+pub fn slice(input: &str, start: usize, len: usize) -> String {
+    let mut out = String::new();
+    let mut index = 0;
+    scan(input, &mut Parser::default(), |_, source, c| {
+        if c.is_none() || (start..start + len).contains(&index) {
+            out.push_str(source);
+        }
+        index += usize::from(c.is_some());
+    });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: u32 = 0xFF5555;
+
+    /// The text of each span with its color and whether it's bold.
+    fn spans(input: &str) -> Vec<(String, Option<u32>, bool)> {
+        let mut parser = Parser::default();
+        scan(input, &mut parser, |parser, _, c| {
+            if let Some(c) = c {
+                parser.push(c);
+            }
+        });
+        parser
+            .spans
+            .into_iter()
+            .map(|span| (span.text, span.style.color, span.style.decorations.bold))
+            .collect()
+    }
+
+    fn span(text: &str, color: Option<u32>, bold: bool) -> (String, Option<u32>, bool) {
+        (text.to_string(), color, bold)
+    }
+
+    #[test]
+    fn tags_style_until_closed() {
+        assert_eq!(
+            spans("<red>a<bold>b</bold>c</red>d"),
+            [
+                span("a", Some(RED), false),
+                span("b", Some(RED), true),
+                span("c", Some(RED), false),
+                span("d", None, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_close_tag_closes_the_last_tag() {
+        assert_eq!(
+            spans("<red><bold>a</>b</>c"),
+            [
+                span("a", Some(RED), true),
+                span("b", Some(RED), false),
+                span("c", None, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_colors_set_the_color() {
+        assert_eq!(spans("<#ff8800>a"), [span("a", Some(0xff8800), false)]);
+        assert_eq!(spans("&#ff8800a"), [span("a", Some(0xff8800), false)]);
+        assert_eq!(
+            spans("<color:#00ff00>a"),
+            [span("a", Some(0x00ff00), false)]
+        );
+    }
+
+    #[test]
+    fn legacy_colors_clear_decorations() {
+        assert_eq!(
+            spans("&lA&cB&lC"),
+            [
+                span("A", None, true),
+                span("B", Some(RED), false),
+                span("C", Some(RED), true),
+            ]
+        );
+        assert_eq!(spans("§cA"), [span("A", Some(RED), false)]);
+    }
+
+    #[test]
+    fn unknown_tags_and_escapes_stay_text() {
+        assert_eq!(spans("<nope>a"), [span("<nope>a", None, false)]);
+        assert_eq!(spans("\\<red>a"), [span("<red>a", None, false)]);
+        assert_eq!(spans("a &z b"), [span("a &z b", None, false)]);
+    }
+
+    #[test]
+    fn reset_clears_everything() {
+        assert_eq!(
+            spans("<red><bold>a<reset>b"),
+            [span("a", Some(RED), true), span("b", None, false)]
+        );
+    }
+
+    #[test]
+    fn gradients_span_the_characters() {
+        let gradient = Fill::Gradient(vec![0x000000, 0xffffff]);
+        assert_eq!(gradient.color_at(0, 3), 0x000000);
+        assert_eq!(gradient.color_at(1, 3), 0x808080);
+        assert_eq!(gradient.color_at(2, 3), 0xffffff);
+        assert_eq!(Fill::Gradient(vec![RED]).color_at(4, 9), RED);
+    }
+
+    #[test]
+    fn visible_len_skips_markup() {
+        assert_eq!(visible_len("<bold>ab</bold>&cc"), 3);
+        assert_eq!(visible_len("\\<x"), 2);
+        assert_eq!(visible_len(""), 0);
+    }
+
+    #[test]
+    fn slice_keeps_markup() {
+        assert_eq!(slice("<red>abc", 1, 1), "<red>b");
+        assert_eq!(slice("a&lbc", 0, 2), "a&lb");
+        assert_eq!(slice("abc", 2, 5), "c");
+    }
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(parse_color("#0a0B0c"), Some(0x0a0b0c));
+        assert_eq!(parse_color("red"), Some(RED));
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("nope"), None);
+    }
+}